@@ -0,0 +1,337 @@
+//! Beacon adapter for the standard Beacon node REST API.
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::debug;
+use crate::{BeaconAdapter, BeaconError, BeaconResult, ProposerIndex, ProposerInfo, Slot, ValidatorPubkey};
+
+/// Slots per epoch on mainnet and all public testnets.
+pub const SLOTS_PER_EPOCH: u64 = 32;
+
+/// Timeout for a single Beacon API request.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Proposers by slot for one epoch, `None` if the node could not provide them.
+type EpochDuties = (u64, Option<HashMap<Slot, ProposerInfo>>);
+
+/// Beacon adapter backed by a beacon node's REST API (Lighthouse, Prysm,
+/// Teku, Nimbus, Lodestar).
+///
+/// The proposer of a slot is taken from the block header at that slot
+/// (`/eth/v1/beacon/headers/{slot}`), so a missed slot is reported as
+/// [`BeaconError::SlotNotFound`]. Pubkeys come from the epoch's proposer
+/// duties (`/eth/v1/validator/duties/proposer/{epoch}`), which are cached per
+/// epoch. Nodes only serve duties for recent epochs, so older proposers are
+/// looked up with `/eth/v1/beacon/states/head/validators/{index}` instead.
+pub struct HttpBeaconAdapter {
+    client: Client,
+    base_url: String,
+    /// Proposer duties of the most recently requested epoch.
+    duties: Mutex<Option<EpochDuties>>,
+}
+
+impl HttpBeaconAdapter {
+    /// Create a new adapter.
+    ///
+    /// # Arguments
+    /// * `base_url` - Beacon node URL, e.g. `http://localhost:5052`
+    pub fn new(base_url: &str) -> BeaconResult<Self> {
+        let client = Client::builder()
+            .timeout(DEFAULT_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| BeaconError::Network(e.into()))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            duties: Mutex::new(None),
+        })
+    }
+
+    /// GET a Beacon API path and decode its `data` field. Returns `None` on 404.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> BeaconResult<Option<T>> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self
+            .client
+            .get(&url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| BeaconError::Network(e.into()))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = response.text().await.map_err(|e| BeaconError::Network(e.into()))?;
+        if !status.is_success() {
+            // Errors are `{"code": 503, "message": "..."}`, but fall back to
+            // the raw body for proxies that answer with something else
+            let message = serde_json::from_str::<ApiError>(&body)
+                .map(|error| error.message)
+                .unwrap_or(body);
+            return Err(BeaconError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        serde_json::from_str::<Envelope<T>>(&body)
+            .map(|envelope| Some(envelope.data))
+            .map_err(|e| BeaconError::Decode(format!("{}: {}", path, e)))
+    }
+
+    /// Proposer of `slot` from the cached duties of its epoch, fetching them
+    /// if the epoch is not cached.
+    async fn duty_for_slot(&self, slot: Slot) -> Option<ProposerInfo> {
+        let epoch = slot / SLOTS_PER_EPOCH;
+        if let Some((cached_epoch, duties)) = &*self.duties.lock().unwrap() {
+            if *cached_epoch == epoch {
+                return duties.as_ref()?.get(&slot).cloned();
+            }
+        }
+
+        let duties = match self
+            .get::<Vec<ProposerDuty>>(&format!("/eth/v1/validator/duties/proposer/{}", epoch))
+            .await
+        {
+            Ok(Some(duties)) => Some(
+                duties
+                    .into_iter()
+                    .map(|duty| (duty.slot, ProposerInfo { index: duty.validator_index, pubkey: duty.pubkey }))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            Ok(None) => None,
+            Err(e) => {
+                debug!("Proposer duties for epoch {} unavailable: {}", epoch, e);
+                None
+            }
+        };
+        let duty = duties.as_ref().and_then(|duties| duties.get(&slot).cloned());
+        *self.duties.lock().unwrap() = Some((epoch, duties));
+        duty
+    }
+}
+
+#[async_trait]
+impl BeaconAdapter for HttpBeaconAdapter {
+    async fn get_proposer_for_slot(&self, slot: Slot) -> BeaconResult<ProposerInfo> {
+        let header: BlockHeaderResponse = self
+            .get(&format!("/eth/v1/beacon/headers/{}", slot))
+            .await?
+            .ok_or(BeaconError::SlotNotFound(slot))?;
+        let index = header.header.message.proposer_index;
+
+        let pubkey = match self.duty_for_slot(slot).await {
+            Some(duty) if duty.index == index => duty.pubkey,
+            _ => self.get_validator_pubkey(index).await?,
+        };
+
+        Ok(ProposerInfo { index, pubkey })
+    }
+
+    async fn get_validator_pubkey(&self, proposer_index: ProposerIndex) -> BeaconResult<ValidatorPubkey> {
+        // A validator's pubkey never changes, so the head state is enough
+        let validator: ValidatorResponse = self
+            .get(&format!("/eth/v1/beacon/states/head/validators/{}", proposer_index))
+            .await?
+            .ok_or(BeaconError::ProposerNotFound(proposer_index))?;
+
+        Ok(validator.validator.pubkey)
+    }
+}
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct ProposerDuty {
+    pubkey: ValidatorPubkey,
+    #[serde(deserialize_with = "quoted_u64")]
+    validator_index: ProposerIndex,
+    #[serde(deserialize_with = "quoted_u64")]
+    slot: Slot,
+}
+
+#[derive(Deserialize)]
+struct BlockHeaderResponse {
+    header: SignedBlockHeader,
+}
+
+#[derive(Deserialize)]
+struct SignedBlockHeader {
+    message: BlockHeaderMessage,
+}
+
+#[derive(Deserialize)]
+struct BlockHeaderMessage {
+    #[serde(deserialize_with = "quoted_u64")]
+    proposer_index: ProposerIndex,
+}
+
+#[derive(Deserialize)]
+struct ValidatorResponse {
+    validator: ValidatorData,
+}
+
+#[derive(Deserialize)]
+struct ValidatorData {
+    pubkey: ValidatorPubkey,
+}
+
+/// The Beacon API encodes integers as decimal strings.
+fn quoted_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Path;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::json;
+
+    const PUBKEY_7: &str = "0xa1d1ad0714035353258038e964ae9675dc0252ee22cea896825c01458e1807bfad2f9969338798548d9858a571f7425c";
+    const PUBKEY_9: &str = "0xb2ff4716ed345b05dd1dfc6a5a9fa70856d8c75dcc9e881dd2f766d5f891326f0d10e96d3a444fb6a5f9a4ae81d2a4e2";
+
+    /// Mock beacon node. Epoch 3 has duties and slot 97 is missed; epoch 0
+    /// has no duties, as for epochs a node no longer serves. Slot 12 was
+    /// proposed by an unknown validator, and slot 13 fails as if syncing.
+    async fn mock_beacon_node() -> String {
+        let app = Router::new()
+            .route(
+                "/eth/v1/validator/duties/proposer/:epoch",
+                get(|Path(epoch): Path<u64>| async move {
+                    if epoch != 3 {
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Json(json!({"code": 400, "message": "Invalid epoch"})),
+                        )
+                            .into_response();
+                    }
+                    Json(json!({
+                        "dependent_root": "0x00",
+                        "execution_optimistic": false,
+                        "data": [
+                            {"pubkey": PUBKEY_7, "validator_index": "7", "slot": "96"},
+                            {"pubkey": PUBKEY_9, "validator_index": "9", "slot": "97"}
+                        ]
+                    }))
+                    .into_response()
+                }),
+            )
+            .route(
+                "/eth/v1/beacon/headers/:slot",
+                get(|Path(slot): Path<u64>| async move {
+                    let proposer_index = match slot {
+                        96 => "7",
+                        5 => "9",
+                        12 => "404",
+                        13 => {
+                            return (
+                                StatusCode::SERVICE_UNAVAILABLE,
+                                Json(json!({"code": 503, "message": "Beacon node is currently syncing"})),
+                            )
+                                .into_response()
+                        }
+                        _ => {
+                            return (
+                                StatusCode::NOT_FOUND,
+                                Json(json!({"code": 404, "message": "NOT_FOUND: beacon block"})),
+                            )
+                                .into_response()
+                        }
+                    };
+                    Json(json!({
+                        "execution_optimistic": false,
+                        "finalized": true,
+                        "data": {
+                            "root": "0x00",
+                            "canonical": true,
+                            "header": {
+                                "message": {"slot": slot.to_string(), "proposer_index": proposer_index},
+                                "signature": "0x00"
+                            }
+                        }
+                    }))
+                    .into_response()
+                }),
+            )
+            .route(
+                "/eth/v1/beacon/states/head/validators/:id",
+                get(|Path(id): Path<String>| async move {
+                    match id.as_str() {
+                        "9" => Json(json!({
+                            "data": {"index": "9", "status": "active_ongoing", "validator": {"pubkey": PUBKEY_9}}
+                        }))
+                        .into_response(),
+                        _ => StatusCode::NOT_FOUND.into_response(),
+                    }
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/", addr)
+    }
+
+    #[tokio::test]
+    async fn test_http_adapter_against_mock_beacon_node() {
+        let adapter = HttpBeaconAdapter::new(&mock_beacon_node().await).unwrap();
+
+        // Pubkey from the epoch's proposer duties
+        let proposer = adapter.get_proposer_for_slot(96).await.unwrap();
+        assert_eq!((proposer.index, proposer.pubkey.as_str()), (7, PUBKEY_7));
+
+        // Missed slot
+        assert!(matches!(
+            adapter.get_proposer_for_slot(97).await,
+            Err(BeaconError::SlotNotFound(97))
+        ));
+
+        // No duties for the epoch, so the pubkey comes from the validators endpoint
+        let proposer = adapter.get_proposer_for_slot(5).await.unwrap();
+        assert_eq!((proposer.index, proposer.pubkey.as_str()), (9, PUBKEY_9));
+
+        // Unknown validator
+        assert!(matches!(
+            adapter.get_proposer_for_slot(12).await,
+            Err(BeaconError::ProposerNotFound(404))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_adapter_maps_api_errors() {
+        let adapter = HttpBeaconAdapter::new(&mock_beacon_node().await).unwrap();
+        match adapter.get_proposer_for_slot(13).await {
+            Err(BeaconError::Api { status, message }) => {
+                assert_eq!(status, 503);
+                assert_eq!(message, "Beacon node is currently syncing");
+            }
+            _ => panic!("expected an API error"),
+        }
+
+        let unreachable = HttpBeaconAdapter::new("http://127.0.0.1:1").unwrap();
+        assert!(matches!(
+            unreachable.get_validator_pubkey(1).await,
+            Err(BeaconError::Network(_))
+        ));
+    }
+}
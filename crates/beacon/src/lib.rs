@@ -2,11 +2,16 @@
 //!
 //! This module provides a trait-based interface for beacon chain adapters
 //! that can map slots to proposers and proposers to validator pubkeys.
-//! The core ingestion does not depend on this at compile time.
+//! Block processing works without an adapter; with one, blocks are tagged
+//! by their proposer's pubkey.
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+mod http;
+
+pub use http::{HttpBeaconAdapter, SLOTS_PER_EPOCH};
+
 /// Represents a validator public key (BLS12-381 public key as hex string).
 pub type ValidatorPubkey = String;
 
@@ -16,6 +21,35 @@ pub type Slot = u64;
 /// Represents a proposer index in the beacon chain.
 pub type ProposerIndex = u64;
 
+/// Maps execution block timestamps to beacon chain slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotClock {
+    /// Unix timestamp of slot 0.
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+}
+
+impl SlotClock {
+    /// Ethereum mainnet: genesis at 2020-12-01 12:00:23 UTC, 12 second slots.
+    pub const MAINNET: SlotClock = SlotClock {
+        genesis_time: 1_606_824_023,
+        seconds_per_slot: 12,
+    };
+
+    /// Get the slot that starts at a given timestamp.
+    ///
+    /// # Arguments
+    /// * `timestamp` - Unix timestamp of a proof-of-stake execution block
+    ///
+    /// # Returns
+    /// The slot, or `None` if the timestamp is before genesis or not at a
+    /// slot boundary (so it does not belong to a beacon block on this chain).
+    pub fn slot_at(&self, timestamp: u64) -> Option<Slot> {
+        let since_genesis = timestamp.checked_sub(self.genesis_time)?;
+        (since_genesis % self.seconds_per_slot == 0).then_some(since_genesis / self.seconds_per_slot)
+    }
+}
+
 /// Error type for beacon chain operations.
 #[derive(Debug, thiserror::Error)]
 pub enum BeaconError {
@@ -25,6 +59,10 @@ pub enum BeaconError {
     SlotNotFound(Slot),
     #[error("Proposer not found: {0}")]
     ProposerNotFound(ProposerIndex),
+    #[error("Beacon API error {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("Invalid response: {0}")]
+    Decode(String),
     #[error("Network error: {0}")]
     Network(#[from] anyhow::Error),
 }
@@ -70,9 +108,7 @@ pub trait BeaconAdapter: Send + Sync {
 /// Placeholder beacon adapter that always returns `NotAvailable`.
 ///
 /// This adapter can be used when a beacon node is not available.
-/// To use a real beacon node, implement the `BeaconAdapter` trait
-/// for a type that connects to a beacon node REST API (e.g., Lighthouse,
-/// Prysm, or Teku).
+/// To use a real beacon node, use [`HttpBeaconAdapter`].
 pub struct PlaceholderBeaconAdapter;
 
 #[async_trait]
@@ -86,3 +122,17 @@ impl BeaconAdapter for PlaceholderBeaconAdapter {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_at() {
+        let clock = SlotClock::MAINNET;
+        assert_eq!(clock.slot_at(1_606_824_023), Some(0));
+        // First proof-of-stake block, 15537394
+        assert_eq!(clock.slot_at(1_663_224_179), Some(4_700_013));
+        assert_eq!(clock.slot_at(1_663_224_180), None);
+        assert_eq!(clock.slot_at(1_606_824_000), None);
+    }
+}
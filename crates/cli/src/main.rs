@@ -1,14 +1,36 @@
 //! CLI application for MEV Africa data collection service.
 
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, NaiveDate};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mev_africa_beacon::{HttpBeaconAdapter, SlotClock};
 use mev_africa_db::DbPool;
-use mev_africa_ingestion::{BlockProcessor, RpcClient};
+use mev_africa_ingestion::{
+    BackfillEngine, BlockFetcher, BlockProcessor, CheckpointStore, CheckpointStream, FinalityStatus,
+    FinalityTracker, GapScanner, HeadWatcher, RateLimitConfig, ReorgDetector, RetryPolicy,
+    RpcClient, RpcEndpoint,
+};
+use mev_africa_ingestion::backfill::{
+    first_block_at_or_after, missing_block_numbers, DEFAULT_BACKFILL_CONCURRENCY,
+};
+use mev_africa_ingestion::builder_identifier::BuilderIdentifier;
+use mev_africa_ingestion::builder_registry::BuilderRegistry;
+use mev_africa_ingestion::reorg::{ReorgEvent, DEFAULT_MAX_REORG_DEPTH};
+use mev_africa_ingestion::retry::DEFAULT_MAX_RETRIES;
 use mev_africa_ingestion::validator_tagger::ValidatorTagger;
 use mev_africa_telemetry::{init_logging, Metrics};
-use std::time::Duration;
-use tokio::time::sleep;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+/// Default Ethereum execution RPC endpoint.
+const DEFAULT_EXECUTION_RPC_URL: &str =
+    "https://ethereum-mainnet.core.chainstack.com/390f7fa4351543e290dc3e4bf9d9058f";
+
+/// How often the ingestion loop rescans the blocks table for gaps.
+const GAP_SCAN_INTERVAL: Duration = Duration::from_secs(600);
+
 #[derive(Parser)]
 #[command(name = "mev-africa")]
 #[command(about = "MEV data collection service for Ethereum validators in Africa")]
@@ -20,11 +42,13 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Start the ingestion service
-    Ingest {
-        /// Ethereum execution RPC URL
-        #[arg(long, default_value = "https://ethereum-mainnet.core.chainstack.com/390f7fa4351543e290dc3e4bf9d9058f")]
-        execution_rpc_url: String,
-
+    Ingest(IngestArgs),
+    /// Backfill an explicit block or date range
+    Backfill(BackfillArgs),
+    /// Find and refetch blocks missing from the database
+    Repair(RepairArgs),
+    /// Import or refresh Africa validators CSV
+    ImportValidators {
         /// Database path
         #[arg(long, default_value = "mev_africa.db")]
         database_path: String,
@@ -32,65 +56,370 @@ enum Commands {
         /// Africa validators CSV path
         #[arg(long, default_value = "examples/africa_validators_example.csv")]
         africa_validators_csv: String,
-
-        /// Poll interval in seconds
-        #[arg(long, default_value = "12")]
-        poll_interval_seconds: u64,
-
-        /// Metrics bind address
-        #[arg(long, default_value = "0.0.0.0:9090")]
-        metrics_bind_address: String,
-
-        /// Log level
-        #[arg(long)]
-        log_level: Option<String>,
-
-        /// Sample output path for audit logs
-        #[arg(long)]
-        sample_output_path: Option<String>,
-
-        /// Start from latest block instead of catching up from database
-        #[arg(long, default_value = "false")]
-        start_from_latest: bool,
     },
-    /// Import or refresh Africa validators CSV
-    ImportValidators {
+    /// Import a known-builder registry
+    ImportBuilders {
         /// Database path
         #[arg(long, default_value = "mev_africa.db")]
         database_path: String,
 
-        /// Africa validators CSV path
-        #[arg(long, default_value = "examples/africa_validators_example.csv")]
-        africa_validators_csv: String,
+        /// Builder registry JSON path
+        #[arg(long, default_value = "examples/builder_registry.json")]
+        builder_registry: String,
+    },
+    /// Attribute stored blocks to builders and name known builders
+    IdentifyBuilders {
+        /// Database path
+        #[arg(long, default_value = "mev_africa.db")]
+        database_path: String,
     },
 }
 
+#[derive(Args)]
+struct IngestArgs {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    enrichment: EnrichmentArgs,
+
+    #[command(flatten)]
+    beacon: BeaconArgs,
+
+    /// Ethereum execution WebSocket URL for newHeads subscriptions (polls if unset)
+    #[arg(long)]
+    execution_ws_url: Option<String>,
+
+    /// Database path
+    #[arg(long, default_value = "mev_africa.db")]
+    database_path: String,
+
+    /// Africa validators CSV path
+    #[arg(long, default_value = "examples/africa_validators_example.csv")]
+    africa_validators_csv: String,
+
+    /// Poll interval in seconds (fallback interval when a WebSocket URL is set)
+    #[arg(long, default_value = "12")]
+    poll_interval_seconds: u64,
+
+    /// Metrics bind address
+    #[arg(long, default_value = "0.0.0.0:9090")]
+    metrics_bind_address: String,
+
+    /// Log level
+    #[arg(long)]
+    log_level: Option<String>,
+
+    /// Sample output path for audit logs
+    #[arg(long)]
+    sample_output_path: Option<String>,
+
+    /// Start from the latest block instead of resuming from the checkpoint,
+    /// skipping any blocks in between
+    #[arg(long, default_value = "false")]
+    start_from_latest: bool,
+
+    /// Maximum number of blocks to walk back when resolving a reorg
+    #[arg(long, default_value_t = DEFAULT_MAX_REORG_DEPTH)]
+    max_reorg_depth: u64,
+
+    /// Number of blocks to request per JSON-RPC batch while catching up
+    #[arg(long, default_value = "10")]
+    rpc_batch_size: usize,
+
+    /// Use the parallel backfill engine when more than this many blocks are behind
+    #[arg(long, default_value = "128")]
+    backfill_threshold: u64,
+
+    /// Number of concurrent fetch workers for backfill
+    #[arg(long, default_value_t = DEFAULT_BACKFILL_CONCURRENCY)]
+    backfill_concurrency: usize,
+
+    /// Which chain head to follow: latest, safe or finalized
+    #[arg(long, value_enum, default_value_t = IngestionMode::Latest)]
+    ingestion_mode: IngestionMode,
+}
+
+#[derive(Args)]
+struct BackfillArgs {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    enrichment: EnrichmentArgs,
+
+    #[command(flatten)]
+    beacon: BeaconArgs,
+
+    /// Database path
+    #[arg(long, default_value = "mev_africa.db")]
+    database_path: String,
+
+    /// First block to backfill (inclusive)
+    #[arg(long, required_unless_present = "from_date", conflicts_with = "from_date")]
+    from_block: Option<u64>,
+
+    /// Last block to backfill (inclusive, defaults to the latest block)
+    #[arg(long, conflicts_with = "to_date")]
+    to_block: Option<u64>,
+
+    /// First day to backfill, as YYYY-MM-DD or an RFC 3339 timestamp (UTC)
+    #[arg(long)]
+    from_date: Option<String>,
+
+    /// Last day to backfill (inclusive), as YYYY-MM-DD or an RFC 3339 timestamp (UTC)
+    #[arg(long)]
+    to_date: Option<String>,
+
+    /// Number of concurrent fetch workers
+    #[arg(long, default_value_t = DEFAULT_BACKFILL_CONCURRENCY)]
+    concurrency: usize,
+
+    /// Number of blocks to request per JSON-RPC batch
+    #[arg(long, default_value = "10")]
+    rpc_batch_size: usize,
+
+    /// Refetch and reprocess blocks that are already stored, updating them in place
+    #[arg(long, default_value = "false")]
+    reprocess: bool,
+
+    /// Log level
+    #[arg(long)]
+    log_level: Option<String>,
+
+    /// Sample output path for audit logs
+    #[arg(long)]
+    sample_output_path: Option<String>,
+}
+
+#[derive(Args)]
+struct RepairArgs {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    enrichment: EnrichmentArgs,
+
+    #[command(flatten)]
+    beacon: BeaconArgs,
+
+    /// Database path
+    #[arg(long, default_value = "mev_africa.db")]
+    database_path: String,
+
+    /// Only list missing ranges, without refetching them
+    #[arg(long, default_value = "false")]
+    dry_run: bool,
+
+    /// Number of concurrent fetch workers
+    #[arg(long, default_value_t = DEFAULT_BACKFILL_CONCURRENCY)]
+    concurrency: usize,
+
+    /// Number of blocks to request per JSON-RPC batch
+    #[arg(long, default_value = "10")]
+    rpc_batch_size: usize,
+
+    /// Log level
+    #[arg(long)]
+    log_level: Option<String>,
+}
+
+/// Execution RPC endpoint options shared by all subcommands that fetch blocks.
+#[derive(Args)]
+struct RpcArgs {
+    /// Ethereum execution RPC URL. Repeat (or comma-separate) to add fallback
+    /// endpoints, in priority order
+    #[arg(long, value_delimiter = ',', default_value = DEFAULT_EXECUTION_RPC_URL)]
+    execution_rpc_url: Vec<String>,
+
+    /// Number of endpoints that must agree on a block hash before the block is stored
+    #[arg(long, default_value = "1")]
+    rpc_quorum: usize,
+
+    /// Retries after a transient RPC failure (transport error, timeout, 5xx or 429)
+    #[arg(long, default_value_t = DEFAULT_MAX_RETRIES)]
+    rpc_max_retries: u32,
+
+    /// Delay before the first retry in milliseconds; doubles with each retry
+    #[arg(long, default_value = "500")]
+    rpc_retry_base_delay_ms: u64,
+
+    /// Timeout for a single RPC request in seconds
+    #[arg(long, default_value = "30")]
+    rpc_timeout_seconds: u64,
+
+    /// Client-side rate limit in compute units per second (unlimited if unset)
+    #[arg(long)]
+    rpc_compute_units_per_second: Option<f64>,
+
+    /// Maximum burst in compute units (defaults to one second worth)
+    #[arg(long, requires = "rpc_compute_units_per_second")]
+    rpc_burst: Option<f64>,
+
+    /// Compute units per UTC day; enrichment pauses once they are spent
+    #[arg(long)]
+    rpc_daily_budget: Option<u64>,
+
+    /// Override a method's compute unit weight, as METHOD=WEIGHT (repeatable).
+    /// Use eth_getBlockByNumber/full for blocks with full transactions
+    #[arg(long, value_parser = parse_method_weight)]
+    rpc_method_weight: Vec<(String, u64)>,
+}
+
+impl RpcArgs {
+    /// Build an RPC client over the configured endpoints, keeping daily
+    /// budget usage in `db`.
+    async fn connect(&self, metrics: Metrics, db: &DbPool) -> anyhow::Result<RpcClient> {
+        let endpoints = self
+            .execution_rpc_url
+            .iter()
+            .enumerate()
+            .map(|(priority, url)| RpcEndpoint::new(url, priority as u32))
+            .collect();
+        let retry_policy = RetryPolicy {
+            max_retries: self.rpc_max_retries,
+            base_delay: Duration::from_millis(self.rpc_retry_base_delay_ms),
+            ..RetryPolicy::default()
+        };
+        RpcClient::from_endpoints(endpoints, metrics)?
+            .with_quorum(self.rpc_quorum)?
+            .with_request_timeout(Duration::from_secs(self.rpc_timeout_seconds))?
+            .with_retry_policy(retry_policy)
+            .with_rate_limit(RateLimitConfig {
+                compute_units_per_second: self.rpc_compute_units_per_second,
+                burst: self.rpc_burst,
+                daily_budget: self.rpc_daily_budget,
+                method_weights: self.rpc_method_weight.iter().cloned().collect(),
+            })
+            .with_budget_store(db.clone())
+            .await
+    }
+}
+
+/// Options for the data fetched alongside each block.
+#[derive(Args)]
+struct EnrichmentArgs {
+    /// Skip transaction receipts; status, gas used and logs are left empty
+    #[arg(long, default_value = "false")]
+    disable_receipts: bool,
+
+    /// Fetch call traces and store internal calls, ETH transfers and failed
+    /// subcalls. Requires the debug or trace namespace on the RPC endpoint
+    #[arg(long, default_value = "false")]
+    enable_traces: bool,
+}
+
+impl EnrichmentArgs {
+    /// Build a block fetcher with the configured enrichment.
+    fn fetcher<'a>(&self, rpc_client: &'a RpcClient) -> BlockFetcher<'a> {
+        BlockFetcher::new(rpc_client)
+            .with_receipts(!self.disable_receipts)
+            .with_traces(self.enable_traces)
+    }
+}
+
+/// Beacon chain options for deriving slots and looking up block proposers.
+#[derive(Args)]
+struct BeaconArgs {
+    /// Beacon adapter used to look up each block's proposer; with `http`,
+    /// blocks are also tagged by proposer pubkey
+    #[arg(long, value_enum, default_value_t = BeaconAdapterKind::None)]
+    beacon_adapter: BeaconAdapterKind,
+
+    /// Beacon node REST API URL used by the `http` adapter
+    #[arg(long, default_value = "http://localhost:5052")]
+    beacon_url: String,
+
+    /// Unix timestamp of the beacon chain genesis, used to derive slots from
+    /// block timestamps (defaults to mainnet)
+    #[arg(long, default_value_t = SlotClock::MAINNET.genesis_time)]
+    beacon_genesis_time: u64,
+
+    /// Length of a beacon chain slot in seconds
+    #[arg(long, default_value_t = SlotClock::MAINNET.seconds_per_slot, value_parser = clap::value_parser!(u64).range(1..))]
+    seconds_per_slot: u64,
+}
+
+impl BeaconArgs {
+    /// Attach the slot clock and the selected beacon adapter to a block processor.
+    fn configure(&self, processor: BlockProcessor) -> anyhow::Result<BlockProcessor> {
+        let slot_clock = SlotClock {
+            genesis_time: self.beacon_genesis_time,
+            seconds_per_slot: self.seconds_per_slot,
+        };
+        if slot_clock != SlotClock::MAINNET {
+            info!(
+                "Deriving slots from genesis time {} with {} second slots",
+                slot_clock.genesis_time, slot_clock.seconds_per_slot
+            );
+        }
+        let processor = processor.with_slot_clock(slot_clock);
+
+        match self.beacon_adapter {
+            BeaconAdapterKind::None => Ok(processor),
+            BeaconAdapterKind::Http => {
+                info!("Looking up block proposers from beacon node {}", self.beacon_url);
+                let adapter = HttpBeaconAdapter::new(&self.beacon_url)?;
+                Ok(processor.with_beacon_adapter(Arc::new(adapter)))
+            }
+        }
+    }
+}
+
+/// Beacon adapter selected with `--beacon-adapter`.
+#[derive(Clone, Copy, ValueEnum)]
+enum BeaconAdapterKind {
+    /// Do not look up proposers
+    None,
+    /// Standard Beacon node REST API at `--beacon-url`
+    Http,
+}
+
+/// Parse a `METHOD=WEIGHT` pair.
+fn parse_method_weight(value: &str) -> anyhow::Result<(String, u64)> {
+    let (method, weight) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("Expected METHOD=WEIGHT, got {}", value))?;
+    Ok((method.trim().to_string(), weight.trim().parse()?))
+}
+
+/// Chain head followed by the ingestion loop.
+#[derive(Clone, Copy, ValueEnum)]
+enum IngestionMode {
+    /// Follow the unfinalized head (`eth_blockNumber`)
+    Latest,
+    /// Follow the `safe` block tag
+    Safe,
+    /// Follow the `finalized` block tag
+    Finalized,
+}
+
+impl From<IngestionMode> for FinalityStatus {
+    fn from(mode: IngestionMode) -> Self {
+        match mode {
+            IngestionMode::Latest => FinalityStatus::Latest,
+            IngestionMode::Safe => FinalityStatus::Safe,
+            IngestionMode::Finalized => FinalityStatus::Finalized,
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Ingest {
-            execution_rpc_url,
-            database_path,
-            africa_validators_csv,
-            poll_interval_seconds,
-            metrics_bind_address,
-            log_level,
-            sample_output_path,
-            start_from_latest,
-        } => {
-            init_logging(log_level.as_deref())?;
-            run_ingestion(
-                &execution_rpc_url,
-                &database_path,
-                &africa_validators_csv,
-                poll_interval_seconds,
-                &metrics_bind_address,
-                sample_output_path,
-                start_from_latest,
-            )
-            .await?;
+        Commands::Ingest(args) => {
+            init_logging(args.log_level.as_deref())?;
+            run_ingestion(&args).await?;
+        }
+        Commands::Backfill(args) => {
+            init_logging(args.log_level.as_deref())?;
+            run_backfill(&args).await?;
+        }
+        Commands::Repair(args) => {
+            init_logging(args.log_level.as_deref())?;
+            run_repair(&args).await?;
         }
         Commands::ImportValidators {
             database_path,
@@ -99,73 +428,214 @@ async fn main() -> anyhow::Result<()> {
             init_logging(None)?;
             import_validators(&database_path, &africa_validators_csv).await?;
         }
+        Commands::ImportBuilders {
+            database_path,
+            builder_registry,
+        } => {
+            init_logging(None)?;
+            import_builders(&database_path, &builder_registry).await?;
+        }
+        Commands::IdentifyBuilders { database_path } => {
+            init_logging(None)?;
+            identify_builders(&database_path).await?;
+        }
     }
 
     Ok(())
 }
 
-async fn run_ingestion(
-    rpc_url: &str,
-    db_path: &str,
-    validators_csv: &str,
-    poll_interval: u64,
-    metrics_addr: &str,
-    sample_output_path: Option<String>,
-    start_from_latest: bool,
-) -> anyhow::Result<()> {
+async fn run_ingestion(args: &IngestArgs) -> anyhow::Result<()> {
     info!("Starting MEV Africa ingestion service");
 
     // Initialize database
-    let db = DbPool::new(db_path).await?;
+    let db = DbPool::new(&args.database_path).await?;
     db.migrate().await?;
 
     // Import validators if CSV exists
-    if std::path::Path::new(validators_csv).exists() {
-        import_validators(db_path, validators_csv).await?;
+    if std::path::Path::new(&args.africa_validators_csv).exists() {
+        import_validators(&args.database_path, &args.africa_validators_csv).await?;
     } else {
-        warn!("Validators CSV not found at {}, continuing without Africa tagging", validators_csv);
+        warn!(
+            "Validators CSV not found at {}, continuing without Africa tagging",
+            args.africa_validators_csv
+        );
     }
 
     // Initialize components
     let metrics = Metrics::new()?;
-    let rpc_client = RpcClient::new(rpc_url, metrics.clone())?;
+    let mut rpc_client = args.rpc.connect(metrics.clone(), &db).await?;
+    if let Some(ws_url) = &args.execution_ws_url {
+        rpc_client = rpc_client.with_ws_url(ws_url);
+    }
     let validator_tagger = ValidatorTagger::new(&db).await?;
-    let processor = BlockProcessor::new(db.clone(), metrics.clone(), validator_tagger, sample_output_path);
+    let finality_mode = FinalityStatus::from(args.ingestion_mode);
+    let processor = BlockProcessor::new(
+        db.clone(),
+        metrics.clone(),
+        validator_tagger,
+        args.sample_output_path.clone(),
+    )
+    .with_builder_identifier(BuilderIdentifier::load(&db).await?)
+    .with_finality_status(finality_mode);
+    let processor = args.beacon.configure(processor)?;
+    let fetcher = args.enrichment.fetcher(&rpc_client);
+    let reorg_detector = ReorgDetector::new(db.clone(), metrics.clone(), args.max_reorg_depth);
+    let finality_tracker = FinalityTracker::new(db.clone(), metrics.clone());
+    let gap_scanner = GapScanner::new(db.clone(), metrics.clone());
+    info!("Following the {} chain head", finality_mode.as_str());
+
+    let mut shutdown = spawn_shutdown_listener();
 
     // Start metrics server
-    start_metrics_server(metrics_addr, metrics.clone()).await?;
+    let metrics_server =
+        start_metrics_server(&args.metrics_bind_address, metrics.clone(), shutdown.clone()).await?;
+
+    // Resume from this stream's checkpoint, falling back to the highest stored
+    // block for databases created before checkpoints existed
+    let checkpoints = CheckpointStore::new(db.clone());
+    let stream = CheckpointStream::Head(finality_mode);
+    let checkpoint = match checkpoints.load(stream).await? {
+        Some((block, _)) => block,
+        None => get_last_processed_block(&db).await?,
+    };
 
     // Main ingestion loop
-    let mut last_block = if start_from_latest {
-        // Start from current latest block
-        let latest = rpc_client.get_latest_block_number().await?;
+    let mut last_block = if args.start_from_latest {
+        // Start from current head block
+        let latest = get_head_block_number(&rpc_client, finality_mode).await?;
+        if checkpoint > 0 && latest > checkpoint + 1 {
+            warn!(
+                "Skipping blocks {} to {} since the last checkpoint; fill them with \
+                 `mev-africa backfill --from-block {} --to-block {}`",
+                checkpoint + 1, latest, checkpoint + 1, latest
+            );
+        }
         info!("Starting from latest block: {}", latest);
         latest
     } else {
-        // Start from last processed block in database
-        get_last_processed_block(&db).await?
+        info!("Resuming {} ingestion after block {}", stream.as_str(), checkpoint);
+        checkpoint
     };
-    let poll_duration = Duration::from_secs(poll_interval);
+    let mut head_watcher = HeadWatcher::new(Duration::from_secs(args.poll_interval_seconds));
+    let mut last_gap_scan: Option<Instant> = None;
 
     loop {
-        match rpc_client.get_latest_block_number().await {
+        if *shutdown.borrow() {
+            break;
+        }
+
+        if last_gap_scan.is_none_or(|t| t.elapsed() >= GAP_SCAN_INTERVAL) {
+            if let Err(e) = gap_scanner.scan().await {
+                warn!("Failed to scan for missing blocks: {}", e);
+            }
+            last_gap_scan = Some(Instant::now());
+        }
+
+        let mut reorged = false;
+        match get_head_block_number(&rpc_client, finality_mode).await {
             Ok(latest_block) => {
-                if latest_block > last_block {
+                if latest_block.saturating_sub(last_block) > args.backfill_threshold {
+                    // The engine stores blocks without comparing parent hashes, so
+                    // check that the stored head was not orphaned while we were away
+                    match check_stored_head(&rpc_client, &reorg_detector, last_block).await {
+                        Ok(Some(reorg)) => {
+                            // Re-ingest the canonical chain from the common ancestor
+                            last_block = reorg.common_ancestor;
+                            reorged = true;
+                            if let Err(e) = checkpoints
+                                .save(stream, last_block, reorg.common_ancestor_hash.as_deref())
+                                .await
+                            {
+                                error!("Failed to save checkpoint after reorg: {}", e);
+                            }
+                        }
+                        Ok(None) => {
+                            let block_numbers: Vec<u64> = ((last_block + 1)..=latest_block).collect();
+                            let report = BackfillEngine::new(
+                                &fetcher,
+                                &processor,
+                                metrics.clone(),
+                                args.backfill_concurrency,
+                                args.rpc_batch_size,
+                            )
+                            .with_checkpoint(checkpoints.clone(), stream)
+                            .with_shutdown(shutdown.clone())
+                            .run(&block_numbers)
+                            .await;
+                            last_block = report.last_committed.unwrap_or(last_block);
+                        }
+                        Err(e) => {
+                            error!("Failed to check block {} for reorg: {}", last_block + 1, e);
+                        }
+                    }
+                } else if latest_block > last_block {
                     info!("Processing blocks from {} to {}", last_block + 1, latest_block);
-                    for block_num in (last_block + 1)..=latest_block {
-                        match rpc_client.get_block(block_num).await {
-                            Ok(Some(block_json)) => {
-                                if let Err(e) = processor.process_block(&block_json).await {
-                                    error!("Failed to process block {}: {}", block_num, e);
-                                } else {
-                                    last_block = block_num;
-                                }
+                    let mut batch_start = last_block + 1;
+                    'batches: while batch_start <= latest_block {
+                        let batch: Vec<u64> = (batch_start..=latest_block)
+                            .take(args.rpc_batch_size.max(1))
+                            .collect();
+                        let batch_end = batch_start + batch.len() as u64 - 1;
+                        batch_start = batch_end + 1;
+
+                        let blocks = match fetcher.fetch_blocks(&batch).await {
+                            Ok(blocks) => blocks,
+                            Err(e) => {
+                                error!("Failed to fetch blocks {} to {}: {}", batch[0], batch_end, e);
+                                break;
                             }
-                            Ok(None) => {
-                                warn!("Block {} not found", block_num);
+                        };
+
+                        for (&block_num, result) in batch.iter().zip(blocks) {
+                            // Finish the in-flight block, then stop
+                            if *shutdown.borrow() {
+                                break 'batches;
                             }
-                            Err(e) => {
-                                error!("Failed to fetch block {}: {}", block_num, e);
+                            match result {
+                                Ok(Some(block_data)) => {
+                                    match reorg_detector.check_and_rollback(&rpc_client, &block_data.block).await {
+                                        Ok(Some(reorg)) => {
+                                            // Re-ingest the canonical chain from the common ancestor
+                                            last_block = reorg.common_ancestor;
+                                            reorged = true;
+                                            if let Err(e) = checkpoints
+                                                .save(stream, last_block, reorg.common_ancestor_hash.as_deref())
+                                                .await
+                                            {
+                                                error!("Failed to save checkpoint after reorg: {}", e);
+                                            }
+                                            break 'batches;
+                                        }
+                                        Ok(None) => {}
+                                        Err(e) => {
+                                            error!("Failed to check block {} for reorg: {}", block_num, e);
+                                            break 'batches;
+                                        }
+                                    }
+
+                                    // Stop at the first failure and retry from here on the
+                                    // next poll, rather than leaving a hole behind
+                                    if let Err(e) = processor.process_block(&block_data).await {
+                                        error!("Failed to process block {}: {}", block_num, e);
+                                        break 'batches;
+                                    }
+                                    last_block = block_num;
+                                    if let Err(e) = checkpoints
+                                        .save(stream, block_num, Some(&block_data.hash()))
+                                        .await
+                                    {
+                                        error!("Failed to save checkpoint at block {}: {}", block_num, e);
+                                        break 'batches;
+                                    }
+                                }
+                                Ok(None) => {
+                                    warn!("Block {} not found, retrying on next poll", block_num);
+                                    break 'batches;
+                                }
+                                Err(e) => {
+                                    error!("Failed to fetch block {}: {}", block_num, e);
+                                    break 'batches;
+                                }
                             }
                         }
                     }
@@ -174,14 +644,271 @@ async fn run_ingestion(
                 }
             }
             Err(e) => {
-                error!("Failed to get latest block number: {}", e);
+                error!("Failed to get {} block number: {}", finality_mode.as_str(), e);
+            }
+        }
+
+        if let Err(e) = finality_tracker.promote(&rpc_client, finality_mode).await {
+            warn!("Failed to update block finality: {}", e);
+        }
+
+        if !reorged {
+            tokio::select! {
+                _ = head_watcher.wait(&rpc_client) => {}
+                _ = shutdown.wait_for(|&stop| stop) => {}
+            }
+        }
+    }
+
+    info!("Stopped ingestion after block {}", last_block);
+    processor.flush_audit()?;
+    rpc_client.save_budget_usage().await;
+    metrics_server.await?;
+    Ok(())
+}
+
+/// Spawn a task that flips the returned flag on SIGINT or SIGTERM.
+///
+/// Installing the handlers replaces the default "terminate immediately"
+/// behaviour, so long-running loops must check the flag between blocks.
+fn spawn_shutdown_listener() -> watch::Receiver<bool> {
+    let (sender, receiver) = watch::channel(false);
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        info!("Shutdown requested, finishing the current block");
+        let _ = sender.send(true);
+        // Keep the channel open so receivers never observe it closing
+        sender.closed().await;
+    });
+    receiver
+}
+
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Check the block after `last_block` against the stored chain, rolling
+/// back to the common ancestor if the stored head was orphaned.
+///
+/// # Arguments
+/// * `rpc_client` - RPC client
+/// * `reorg_detector` - Reorg detector
+/// * `last_block` - Highest block ingested so far
+async fn check_stored_head(
+    rpc_client: &RpcClient,
+    reorg_detector: &ReorgDetector,
+    last_block: u64,
+) -> anyhow::Result<Option<ReorgEvent>> {
+    let block = rpc_client
+        .get_block(last_block + 1)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Block {} not found", last_block + 1))?;
+    reorg_detector.check_and_rollback(rpc_client, &block).await
+}
+
+/// Get the number of the chain head the ingestion loop follows.
+async fn get_head_block_number(rpc_client: &RpcClient, mode: FinalityStatus) -> anyhow::Result<u64> {
+    match mode {
+        FinalityStatus::Latest => rpc_client.get_latest_block_number().await,
+        _ => rpc_client
+            .get_tagged_block(mode.as_str())
+            .await?
+            .map(|(number, _)| number)
+            .ok_or_else(|| anyhow::anyhow!("Node returned no {} block", mode.as_str())),
+    }
+}
+
+async fn run_backfill(args: &BackfillArgs) -> anyhow::Result<()> {
+    let db = DbPool::new(&args.database_path).await?;
+    db.migrate().await?;
+
+    let metrics = Metrics::new()?;
+    let rpc_client = args.rpc.connect(metrics.clone(), &db).await?;
+    let validator_tagger = ValidatorTagger::new(&db).await?;
+    let processor = BlockProcessor::new(
+        db.clone(),
+        metrics.clone(),
+        validator_tagger,
+        args.sample_output_path.clone(),
+    )
+    .with_builder_identifier(BuilderIdentifier::load(&db).await?);
+    let processor = args.beacon.configure(processor)?;
+
+    let latest = rpc_client.get_latest_block_number().await?;
+    let from_block = match (args.from_block, &args.from_date) {
+        (Some(block), _) => block,
+        (None, Some(date)) => first_block_at_or_after(&rpc_client, latest, parse_date_bound(date, false)?).await?,
+        (None, None) => unreachable!("clap requires --from-block or --from-date"),
+    };
+    let to_block = match (args.to_block, &args.to_date) {
+        (Some(block), _) => block,
+        // The first block after the range, minus one
+        (None, Some(date)) => first_block_at_or_after(&rpc_client, latest, parse_date_bound(date, true)?)
+            .await?
+            .saturating_sub(1),
+        (None, None) => latest,
+    };
+
+    if from_block > to_block {
+        return Err(anyhow::anyhow!("Empty backfill range: {} to {}", from_block, to_block));
+    }
+
+    let checkpoints = CheckpointStore::new(db.clone());
+    let block_numbers = if args.reprocess {
+        // Stored blocks are not skipped here, so continue after the
+        // checkpoint of an interrupted run instead
+        let resume_from = match checkpoints.load(CheckpointStream::Backfill).await? {
+            Some((block, _)) if (from_block..to_block).contains(&block) => {
+                info!("Resuming reprocessing after block {} from the backfill checkpoint", block);
+                block + 1
             }
+            _ => from_block,
+        };
+        info!("Reprocessing {} blocks from {} to {}", to_block - resume_from + 1, resume_from, to_block);
+        (resume_from..=to_block).collect()
+    } else {
+        let missing = missing_block_numbers(&db, from_block, to_block).await?;
+        info!(
+            "Backfill range {} to {}: {} blocks already stored, {} to fetch",
+            from_block,
+            to_block,
+            (to_block - from_block + 1) - missing.len() as u64,
+            missing.len()
+        );
+        missing
+    };
+    if block_numbers.is_empty() {
+        return Ok(());
+    }
+
+    let fetcher = args.enrichment.fetcher(&rpc_client);
+    let report = BackfillEngine::new(
+        &fetcher,
+        &processor,
+        metrics.clone(),
+        args.concurrency,
+        args.rpc_batch_size,
+    )
+    .with_checkpoint(checkpoints.clone(), CheckpointStream::Backfill)
+    .with_shutdown(spawn_shutdown_listener())
+    .run(&block_numbers)
+    .await;
+    processor.flush_audit()?;
+    rpc_client.save_budget_usage().await;
+    if report.failure.is_none() && !report.interrupted {
+        checkpoints.clear(CheckpointStream::Backfill).await?;
+    }
+
+    // Backfilled history is usually already final
+    FinalityTracker::new(db.clone(), metrics)
+        .promote(&rpc_client, FinalityStatus::Latest)
+        .await?;
+
+    match report.failure {
+        Some((block_num, e)) => Err(anyhow::anyhow!(
+            "Backfill stopped at block {}: {}. Re-run the same command to resume.",
+            block_num,
+            e
+        )),
+        None if report.interrupted => {
+            info!("Backfill interrupted. Re-run the same command to resume.");
+            Ok(())
         }
+        None => Ok(()),
+    }
+}
+
+async fn run_repair(args: &RepairArgs) -> anyhow::Result<()> {
+    let db = DbPool::new(&args.database_path).await?;
+    db.migrate().await?;
+
+    let metrics = Metrics::new()?;
+    let gap_scanner = GapScanner::new(db.clone(), metrics.clone());
+    let gaps = gap_scanner.scan().await?;
+    for gap in &gaps {
+        info!("Missing blocks {} to {} ({} blocks)", gap.start, gap.end, gap.len());
+    }
+    if gaps.is_empty() || args.dry_run {
+        return Ok(());
+    }
 
-        sleep(poll_duration).await;
+    let rpc_client = args.rpc.connect(metrics.clone(), &db).await?;
+    let validator_tagger = ValidatorTagger::new(&db).await?;
+    let processor = BlockProcessor::new(db.clone(), metrics.clone(), validator_tagger, None)
+        .with_builder_identifier(BuilderIdentifier::load(&db).await?);
+    let processor = args.beacon.configure(processor)?;
+
+    let block_numbers: Vec<u64> = gaps.iter().flat_map(|gap| gap.start..=gap.end).collect();
+    let fetcher = args.enrichment.fetcher(&rpc_client);
+    let report = BackfillEngine::new(
+        &fetcher,
+        &processor,
+        metrics.clone(),
+        args.concurrency,
+        args.rpc_batch_size,
+    )
+    .with_shutdown(spawn_shutdown_listener())
+    .run(&block_numbers)
+    .await;
+    rpc_client.save_budget_usage().await;
+
+    FinalityTracker::new(db.clone(), metrics)
+        .promote(&rpc_client, FinalityStatus::Latest)
+        .await?;
+    gap_scanner.scan().await?;
+
+    match report.failure {
+        Some((block_num, e)) => Err(anyhow::anyhow!("Repair stopped at block {}: {}", block_num, e)),
+        None => Ok(()),
     }
 }
 
+/// Parse a `YYYY-MM-DD` date or RFC 3339 timestamp into a Unix timestamp.
+///
+/// With `end_of_range` set, returns the first second after the bound, so a
+/// bare date covers the whole day.
+fn parse_date_bound(value: &str, end_of_range: bool) -> anyhow::Result<u64> {
+    let timestamp = if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let day = if end_of_range {
+            date.succ_opt().ok_or_else(|| anyhow::anyhow!("Date out of range: {}", value))?
+        } else {
+            date
+        };
+        day.and_hms_opt(0, 0, 0)
+            .ok_or_else(|| anyhow::anyhow!("Invalid date: {}", value))?
+            .and_utc()
+            .timestamp()
+    } else {
+        let timestamp = DateTime::parse_from_rfc3339(value)
+            .map_err(|e| anyhow::anyhow!("Invalid date {}: {}", value, e))?
+            .timestamp();
+        if end_of_range { timestamp + 1 } else { timestamp }
+    };
+
+    u64::try_from(timestamp).map_err(|_| anyhow::anyhow!("Date before the Unix epoch: {}", value))
+}
+
 async fn get_last_processed_block(db: &DbPool) -> anyhow::Result<u64> {
     let result: Option<i64> = sqlx::query_scalar(
         "SELECT MAX(block_number) FROM blocks"
@@ -223,6 +950,43 @@ async fn import_validators(db_path: &str, csv_path: &str) -> anyhow::Result<()>
     Ok(())
 }
 
+async fn import_builders(db_path: &str, registry_path: &str) -> anyhow::Result<()> {
+    info!("Importing builder registry from {}", registry_path);
+
+    let db = DbPool::new(db_path).await?;
+    db.migrate().await?;
+
+    let registry = BuilderRegistry::from_path(registry_path)?;
+    let report = registry.import(&db).await?;
+    for conflict in &report.conflicts {
+        warn!("{}", conflict);
+    }
+    for builder in &report.unmatched {
+        info!("No stored blocks from {}", builder);
+    }
+    info!(
+        "Imported {} builders (version {}) with {} fee recipients and {} pubkeys: {} conflicts, {} unmatched",
+        report.builders,
+        registry.version,
+        report.fee_recipients,
+        report.pubkeys,
+        report.conflicts.len(),
+        report.unmatched.len()
+    );
+
+    // Re-attribute stored blocks with the new registry
+    BuilderIdentifier::load(&db).await?.identify_stored_blocks(&db).await?;
+    Ok(())
+}
+
+async fn identify_builders(db_path: &str) -> anyhow::Result<()> {
+    let db = DbPool::new(db_path).await?;
+    db.migrate().await?;
+
+    BuilderIdentifier::load(&db).await?.identify_stored_blocks(&db).await?;
+    Ok(())
+}
+
 #[derive(serde::Deserialize)]
 struct ValidatorRecord {
     fee_recipient: String,
@@ -231,7 +995,14 @@ struct ValidatorRecord {
     country: Option<String>,
 }
 
-async fn start_metrics_server(addr: &str, metrics: Metrics) -> anyhow::Result<()> {
+/// Serve `/metrics` until `shutdown` turns true.
+///
+/// Returns the server task so callers can wait for in-flight scrapes to finish.
+async fn start_metrics_server(
+    addr: &str,
+    metrics: Metrics,
+    mut shutdown: watch::Receiver<bool>,
+) -> anyhow::Result<JoinHandle<()>> {
     use axum::{
         extract::State,
         http::StatusCode,
@@ -259,13 +1030,17 @@ async fn start_metrics_server(addr: &str, metrics: Metrics) -> anyhow::Result<()
     let listener = tokio::net::TcpListener::bind(addr).await?;
     info!("Metrics server listening on http://{}", addr);
     
-    tokio::spawn(async move {
-        if let Err(e) = axum::serve(listener, app).await {
+    let server = tokio::spawn(async move {
+        let stopped = async move {
+            let _ = shutdown.wait_for(|&stop| stop).await;
+        };
+        if let Err(e) = axum::serve(listener, app).with_graceful_shutdown(stopped).await {
             error!("Metrics server error: {}", e);
         }
+        info!("Metrics server stopped");
     });
 
-    Ok(())
+    Ok(server)
 }
 
 
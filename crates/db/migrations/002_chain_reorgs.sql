@@ -0,0 +1,22 @@
+-- Chain reorganization tracking
+-- Schema version: 2
+
+-- One row per detected reorg. Orphaned blocks are deleted from `blocks`
+-- (their transactions and annotations go with them) and re-ingested from
+-- the canonical chain; this table keeps the record of what was replaced.
+CREATE TABLE IF NOT EXISTS reorgs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    common_ancestor_number INTEGER NOT NULL,
+    common_ancestor_hash TEXT,
+    depth INTEGER NOT NULL,
+    old_head_number INTEGER NOT NULL,
+    old_head_hash TEXT NOT NULL,
+    new_head_hash TEXT NOT NULL,
+    orphaned_block_count INTEGER NOT NULL,
+    detected_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_reorgs_ancestor ON reorgs(common_ancestor_number);
+CREATE INDEX IF NOT EXISTS idx_reorgs_detected_at ON reorgs(detected_at);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (2);
@@ -0,0 +1,10 @@
+-- Block finality tracking
+-- Schema version: 3
+
+-- Finality status of each stored block: 'latest', 'safe' or 'finalized'.
+-- Blocks are promoted as the chain's safe and finalized heads advance.
+ALTER TABLE blocks ADD COLUMN finality_status TEXT NOT NULL DEFAULT 'latest';
+
+CREATE INDEX IF NOT EXISTS idx_blocks_finality_status ON blocks(finality_status);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (3);
@@ -0,0 +1,14 @@
+-- Ingestion checkpoints
+-- Schema version: 4
+
+-- Last block committed by each ingestion stream ('latest', 'safe',
+-- 'finalized' or 'backfill'). Ingestion resumes from here instead of
+-- MAX(block_number), which is wrong once the table has gaps.
+CREATE TABLE IF NOT EXISTS ingestion_checkpoints (
+    stream TEXT PRIMARY KEY,
+    block_number INTEGER NOT NULL,
+    block_hash TEXT,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (4);
@@ -0,0 +1,30 @@
+-- Transaction receipts and logs
+-- Schema version: 5
+
+-- Receipt fields. NULL until the receipt has been fetched.
+ALTER TABLE transactions ADD COLUMN status INTEGER; -- 1 success, 0 reverted
+ALTER TABLE transactions ADD COLUMN gas_used INTEGER;
+ALTER TABLE transactions ADD COLUMN effective_gas_price TEXT; -- Stored as string to preserve precision
+ALTER TABLE transactions ADD COLUMN contract_address TEXT;
+
+-- Event logs emitted by each transaction
+CREATE TABLE IF NOT EXISTS logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_id INTEGER NOT NULL,
+    log_index INTEGER NOT NULL, -- Position of the log within the block
+    address TEXT NOT NULL,
+    topic0 TEXT,
+    topic1 TEXT,
+    topic2 TEXT,
+    topic3 TEXT,
+    data TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    UNIQUE(transaction_id, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_logs_transaction_id ON logs(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_logs_address ON logs(address);
+CREATE INDEX IF NOT EXISTS idx_logs_topic0 ON logs(topic0);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (5);
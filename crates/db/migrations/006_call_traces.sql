@@ -0,0 +1,57 @@
+-- Call traces
+-- Schema version: 6
+
+-- Internal calls made by each transaction (the top-level call is not stored)
+CREATE TABLE IF NOT EXISTS internal_calls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_id INTEGER NOT NULL,
+    trace_address TEXT NOT NULL, -- Dot-separated path in the call tree, e.g. 0.2
+    depth INTEGER NOT NULL,
+    call_type TEXT NOT NULL, -- CALL, DELEGATECALL, STATICCALL, CREATE, ...
+    from_address TEXT NOT NULL,
+    to_address TEXT,
+    value TEXT, -- Wei, stored as string to preserve precision. NULL when zero
+    gas_used INTEGER,
+    selector TEXT, -- First 4 bytes of the call input
+    error TEXT,
+    reverted BOOLEAN NOT NULL DEFAULT 0, -- The call or one of its callers failed
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    UNIQUE(transaction_id, trace_address)
+);
+
+-- Internal ETH transfers that were not reverted
+CREATE TABLE IF NOT EXISTS internal_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_id INTEGER NOT NULL,
+    trace_address TEXT NOT NULL,
+    from_address TEXT NOT NULL,
+    to_address TEXT,
+    value TEXT NOT NULL, -- Wei, stored as string to preserve precision
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    UNIQUE(transaction_id, trace_address)
+);
+
+-- Internal calls that failed
+CREATE TABLE IF NOT EXISTS failed_subcalls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_id INTEGER NOT NULL,
+    trace_address TEXT NOT NULL,
+    call_type TEXT NOT NULL,
+    from_address TEXT NOT NULL,
+    to_address TEXT,
+    selector TEXT,
+    error TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (transaction_id) REFERENCES transactions(id) ON DELETE CASCADE,
+    UNIQUE(transaction_id, trace_address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_internal_calls_transaction_id ON internal_calls(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_internal_calls_to_address ON internal_calls(to_address);
+CREATE INDEX IF NOT EXISTS idx_internal_transfers_transaction_id ON internal_transfers(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_internal_transfers_to_address ON internal_transfers(to_address);
+CREATE INDEX IF NOT EXISTS idx_failed_subcalls_transaction_id ON failed_subcalls(transaction_id);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (6);
@@ -0,0 +1,14 @@
+-- Canonical MEV reason codes
+-- Schema version: 7
+
+-- Earlier versions of the block processor stored their own names for two
+-- reason codes. Rewrite them to the names used by mev-africa-heuristics.
+UPDATE transactions
+SET mev_reason_codes = REPLACE(
+    REPLACE(mev_reason_codes, '"high_priority_fee_outlier"', '"high_priority_fee"'),
+    '"repeated_sender_sequence"', '"repeated_sender"'
+)
+WHERE mev_reason_codes LIKE '%high_priority_fee_outlier%'
+   OR mev_reason_codes LIKE '%repeated_sender_sequence%';
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (7);
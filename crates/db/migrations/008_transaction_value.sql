@@ -0,0 +1,9 @@
+-- Transaction values
+-- Schema version: 8
+
+-- Wei amounts are stored as TEXT holding the exact amount in wei as a
+-- base-10 integer, without sign, separators or leading zeros.
+-- NULL for transactions stored before this column existed.
+ALTER TABLE transactions ADD COLUMN value TEXT;
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (8);
@@ -0,0 +1,19 @@
+-- Fee accounting
+-- Schema version: 9
+
+-- Priority fee per gas the transaction actually paid: min(maxPriorityFee,
+-- maxFee - baseFee) for EIP-1559 transactions, gasPrice - baseFee otherwise.
+-- NULL for transactions stored before this column existed.
+ALTER TABLE transactions ADD COLUMN effective_priority_fee TEXT;
+
+-- effective_priority_fee * gasUsed. NULL without a receipt.
+ALTER TABLE transactions ADD COLUMN priority_fee_paid TEXT;
+
+-- base_fee * gas_used. NULL for blocks stored before this column existed.
+ALTER TABLE blocks ADD COLUMN burned_fees TEXT;
+
+-- Sum of priority_fee_paid over the block's transactions: what the fee
+-- recipient actually earned from tips. NULL when receipts were not fetched.
+ALTER TABLE blocks ADD COLUMN priority_revenue TEXT;
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (9);
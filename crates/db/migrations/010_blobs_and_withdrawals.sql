@@ -0,0 +1,35 @@
+-- Blob and withdrawal fields
+-- Schema version: 10
+
+-- Cancun (EIP-4844 / EIP-4788) and Shanghai (EIP-4895) header fields.
+-- NULL for blocks before the fork that introduced them.
+ALTER TABLE blocks ADD COLUMN blob_gas_used INTEGER;
+ALTER TABLE blocks ADD COLUMN excess_blob_gas INTEGER;
+ALTER TABLE blocks ADD COLUMN withdrawals_root TEXT;
+ALTER TABLE blocks ADD COLUMN parent_beacon_block_root TEXT;
+
+-- EIP-2718 type: 0 legacy, 1 access list, 2 EIP-1559, 3 blob, 4 set code
+ALTER TABLE transactions ADD COLUMN transaction_type INTEGER;
+-- Wei per blob gas, NULL for non-blob transactions
+ALTER TABLE transactions ADD COLUMN max_fee_per_blob_gas TEXT;
+-- Number of blob versioned hashes, NULL for non-blob transactions
+ALTER TABLE transactions ADD COLUMN blob_hash_count INTEGER;
+
+-- Validator withdrawals included in each block
+CREATE TABLE IF NOT EXISTS withdrawals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    block_id INTEGER NOT NULL,
+    withdrawal_index INTEGER NOT NULL,
+    validator_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    amount TEXT NOT NULL, -- Wei, stored as string to preserve precision
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (block_id) REFERENCES blocks(id) ON DELETE CASCADE,
+    UNIQUE(block_id, withdrawal_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_withdrawals_block_id ON withdrawals(block_id);
+CREATE INDEX IF NOT EXISTS idx_withdrawals_validator_index ON withdrawals(validator_index);
+CREATE INDEX IF NOT EXISTS idx_withdrawals_address ON withdrawals(address);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (10);
@@ -0,0 +1,19 @@
+-- Builder identification
+-- Schema version: 11
+
+-- Block extraData as 0x-prefixed hex, and decoded as UTF-8 with
+-- non-printable characters removed (NULL when nothing printable is left)
+ALTER TABLE blocks ADD COLUMN extra_data TEXT;
+ALTER TABLE blocks ADD COLUMN extra_data_text TEXT;
+
+-- Builder attributed to the block and how confident the attribution is
+-- ('high', 'medium' or 'low'). NULL when no builder was identified.
+ALTER TABLE blocks ADD COLUMN builder_name TEXT;
+ALTER TABLE blocks ADD COLUMN builder_confidence TEXT;
+
+-- Confidence of builders.builder_name, NULL while the builder is unnamed
+ALTER TABLE builders ADD COLUMN confidence TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_blocks_builder_name ON blocks(builder_name);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (11);
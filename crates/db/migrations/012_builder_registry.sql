@@ -0,0 +1,32 @@
+-- Builder registry
+-- Schema version: 12
+
+-- Builders imported from a registry file with `import-builders`. Each import
+-- replaces the previous one, so the same file always yields the same rows.
+CREATE TABLE IF NOT EXISTS builder_registry (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE, -- Canonical builder name
+    website TEXT,
+    extra_data_patterns TEXT NOT NULL DEFAULT '[]', -- JSON array of regexes
+    registry_version INTEGER NOT NULL, -- Version of the registry file it came from
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- BLS pubkeys builders sign relay bids with
+CREATE TABLE IF NOT EXISTS builder_pubkeys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    builder_registry_id INTEGER NOT NULL,
+    pubkey TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (builder_registry_id) REFERENCES builder_registry(id) ON DELETE CASCADE
+);
+
+-- Registry entry a fee recipient was imported from, NULL for builders that
+-- were only identified while ingesting
+ALTER TABLE builders ADD COLUMN registry_builder_id INTEGER REFERENCES builder_registry(id);
+
+CREATE INDEX IF NOT EXISTS idx_builder_pubkeys_builder_registry_id ON builder_pubkeys(builder_registry_id);
+CREATE INDEX IF NOT EXISTS idx_builders_registry_builder_id ON builders(registry_builder_id);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (12);
@@ -0,0 +1,12 @@
+-- Proposer payments
+-- Schema version: 13
+
+-- Payment from the builder (the block's fee recipient) to the proposer in
+-- the last transaction of MEV-Boost blocks. NULL when the block has none.
+ALTER TABLE blocks ADD COLUMN proposer_payment_recipient TEXT;
+ALTER TABLE blocks ADD COLUMN proposer_payment_amount TEXT; -- Wei, stored as string to preserve precision
+ALTER TABLE blocks ADD COLUMN proposer_payment_tx_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_blocks_proposer_payment_recipient ON blocks(proposer_payment_recipient);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (13);
@@ -0,0 +1,16 @@
+-- Block proposers
+-- Schema version: 14
+
+-- Beacon chain slot derived from the block timestamp, NULL before the merge
+ALTER TABLE blocks ADD COLUMN slot INTEGER;
+
+-- Proposer of the slot according to the beacon adapter. NULL when no
+-- adapter is configured or it could not provide the proposer.
+ALTER TABLE blocks ADD COLUMN proposer_index INTEGER;
+ALTER TABLE blocks ADD COLUMN proposer_pubkey TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_blocks_slot ON blocks(slot);
+CREATE INDEX IF NOT EXISTS idx_blocks_proposer_pubkey ON blocks(proposer_pubkey);
+CREATE INDEX IF NOT EXISTS idx_validators_validator_pubkey ON validators(validator_pubkey);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (14);
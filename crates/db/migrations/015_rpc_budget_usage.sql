@@ -0,0 +1,12 @@
+-- RPC budget usage
+-- Schema version: 15
+
+-- Compute units charged against --rpc-daily-budget per UTC day, so the
+-- budget is not reset when the service restarts
+CREATE TABLE IF NOT EXISTS rpc_budget_usage (
+    day TEXT PRIMARY KEY, -- UTC date, YYYY-MM-DD
+    compute_units INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (15);
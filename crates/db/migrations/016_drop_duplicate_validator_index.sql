@@ -0,0 +1,8 @@
+-- Drop duplicate validator pubkey index
+-- Schema version: 16
+
+-- Migration 014 indexed validators(validator_pubkey) again, which
+-- idx_validators_pubkey from the initial schema already covers
+DROP INDEX IF EXISTS idx_validators_validator_pubkey;
+
+INSERT OR IGNORE INTO schema_version (version) VALUES (16);
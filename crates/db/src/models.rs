@@ -1,5 +1,11 @@
 //! Database models and types.
+//!
+//! Wei amounts (fees, gas prices and values) are stored as TEXT holding the
+//! exact amount in wei as a base-10 integer, without sign, separators or
+//! leading zeros. Use [`format_wei`] and [`parse_wei`] to convert them, and
+//! [`wei_to_gwei`] / [`wei_to_eth`] for display.
 
+use alloy::primitives::U256;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -13,10 +19,27 @@ pub struct Block {
     pub parent_hash: String,
     pub timestamp: DateTime<Utc>,
     pub fee_recipient: String,
-    pub base_fee: String, // Stored as string to preserve precision
+    pub base_fee: String, // Wei, see the module docs
     pub gas_used: i64,
-    pub total_priority_fees: String, // Stored as string to preserve precision
+    pub total_priority_fees: String, // Wei, sum of effective priority fees per gas
+    pub burned_fees: Option<String>, // Wei, base_fee * gas_used
+    pub priority_revenue: Option<String>, // Wei, NULL without receipts
+    pub blob_gas_used: Option<i64>,
+    pub excess_blob_gas: Option<i64>,
+    pub withdrawals_root: Option<String>,
+    pub parent_beacon_block_root: Option<String>,
+    pub extra_data: Option<String>, // 0x-prefixed hex
+    pub extra_data_text: Option<String>, // Printable UTF-8 content of extra_data
+    pub builder_name: Option<String>,
+    pub builder_confidence: Option<String>, // 'high', 'medium' or 'low'
+    pub proposer_payment_recipient: Option<String>,
+    pub proposer_payment_amount: Option<String>, // Wei, see the module docs
+    pub proposer_payment_tx_hash: Option<String>,
+    pub slot: Option<i64>,
+    pub proposer_index: Option<i64>,
+    pub proposer_pubkey: Option<String>,
     pub is_africa_tagged: bool,
+    pub finality_status: String, // 'latest', 'safe' or 'finalized'
     pub created_at: DateTime<Utc>,
 }
 
@@ -28,11 +51,92 @@ pub struct Transaction {
     pub tx_hash: String,
     pub position_index: i64,
     pub sender_address: String,
-    pub max_priority_fee: String,
+    pub max_priority_fee: String, // Wei, see the module docs
+    pub value: Option<String>, // Wei, NULL for rows stored before values were recorded
     pub calldata_summary: Option<String>,
     pub log_summary: Option<String>,
     pub is_mev_candidate: bool,
     pub mev_reason_codes: Option<String>, // JSON array of reason codes
+    pub status: Option<i64>, // 1 success, 0 reverted, NULL without receipt
+    pub gas_used: Option<i64>,
+    pub effective_gas_price: Option<String>, // Wei, see the module docs
+    pub contract_address: Option<String>,
+    pub effective_priority_fee: Option<String>, // Wei per gas actually paid as tip
+    pub priority_fee_paid: Option<String>, // Wei, NULL without receipt
+    pub transaction_type: Option<i64>,
+    pub max_fee_per_blob_gas: Option<String>, // Wei, NULL for non-blob transactions
+    pub blob_hash_count: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Event log emitted by a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Log {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub log_index: i64,
+    pub address: String,
+    pub topic0: Option<String>,
+    pub topic1: Option<String>,
+    pub topic2: Option<String>,
+    pub topic3: Option<String>,
+    pub data: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Internal call made by a transaction, from its call trace.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InternalCall {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub trace_address: String,
+    pub depth: i64,
+    pub call_type: String,
+    pub from_address: String,
+    pub to_address: Option<String>,
+    pub value: Option<String>,
+    pub gas_used: Option<i64>,
+    pub selector: Option<String>,
+    pub error: Option<String>,
+    pub reverted: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Internal ETH transfer made by a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InternalTransfer {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub trace_address: String,
+    pub from_address: String,
+    pub to_address: Option<String>,
+    pub value: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Internal call that failed.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FailedSubcall {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub trace_address: String,
+    pub call_type: String,
+    pub from_address: String,
+    pub to_address: Option<String>,
+    pub selector: Option<String>,
+    pub error: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Validator withdrawal included in a block.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Withdrawal {
+    pub id: i64,
+    pub block_id: i64,
+    pub withdrawal_index: i64,
+    pub validator_index: i64,
+    pub address: String,
+    pub amount: String, // Wei, see the module docs
     pub created_at: DateTime<Utc>,
 }
 
@@ -43,10 +147,33 @@ pub struct Builder {
     pub fee_recipient: String,
     pub builder_name: Option<String>,
     pub is_known: bool,
+    pub confidence: Option<String>, // 'high', 'medium' or 'low'
+    pub registry_builder_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Builder imported from a registry file.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BuilderRegistryEntry {
+    pub id: i64,
+    pub name: String,
+    pub website: Option<String>,
+    pub extra_data_patterns: String, // JSON array of regexes
+    pub registry_version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// BLS pubkey of a registry builder.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BuilderPubkey {
+    pub id: i64,
+    pub builder_registry_id: i64,
+    pub pubkey: String,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Validator information from CSV import.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Validator {
@@ -70,8 +197,35 @@ pub struct Annotation {
     pub created_at: DateTime<Utc>,
 }
 
+/// Record of a detected chain reorganization.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Reorg {
+    pub id: i64,
+    pub common_ancestor_number: i64,
+    pub common_ancestor_hash: Option<String>,
+    pub depth: i64,
+    pub old_head_number: i64,
+    pub old_head_hash: String,
+    pub new_head_hash: String,
+    pub orphaned_block_count: i64,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Resume point of an ingestion stream.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IngestionCheckpoint {
+    pub stream: String,
+    pub block_number: i64,
+    pub block_hash: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// MEV reason codes for transaction classification.
+///
+/// Stored in `transactions.mev_reason_codes` as a JSON array of
+/// [`MevReasonCode::as_str`] values.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum MevReasonCode {
     HighPriorityFee,
     RepeatedSender,
@@ -90,3 +244,68 @@ impl MevReasonCode {
     }
 }
 
+/// Wei in one gwei.
+pub const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+/// Wei in one ether.
+pub const WEI_PER_ETH: u64 = 1_000_000_000_000_000_000;
+
+/// Error returned when a stored wei amount is not a base-10 integer.
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid wei amount {0:?}")]
+pub struct InvalidWei(pub String);
+
+/// Format a wei amount for storage.
+pub fn format_wei(wei: U256) -> String {
+    wei.to_string()
+}
+
+/// Parse a wei amount stored by [`format_wei`].
+pub fn parse_wei(value: &str) -> Result<U256, InvalidWei> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(InvalidWei(value.to_string()));
+    }
+    U256::from_str_radix(value, 10).map_err(|_| InvalidWei(value.to_string()))
+}
+
+/// Convert wei to gwei, e.g. `1500000000` to `"1.5"`.
+pub fn wei_to_gwei(wei: U256) -> String {
+    format_decimal(wei, WEI_PER_GWEI, 9)
+}
+
+/// Convert wei to ether, e.g. `1230000000000000000` to `"1.23"`.
+pub fn wei_to_eth(wei: U256) -> String {
+    format_decimal(wei, WEI_PER_ETH, 18)
+}
+
+/// Format `value / unit` exactly, dropping trailing fractional zeros.
+fn format_decimal(value: U256, unit: u64, decimals: usize) -> String {
+    let unit = U256::from(unit);
+    let (whole, fraction) = (value / unit, value % unit);
+    if fraction.is_zero() {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0>width$}", fraction.to_string(), width = decimals);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wei_round_trip_and_units() {
+        let max = U256::MAX;
+        assert_eq!(parse_wei(&format_wei(max)).unwrap(), max);
+        assert!(parse_wei("0x10").is_err());
+        assert!(parse_wei("-1").is_err());
+        assert!(parse_wei("1_000").is_err());
+        assert!(parse_wei("").is_err());
+
+        assert_eq!(wei_to_gwei(U256::from(1_500_000_000u64)), "1.5");
+        assert_eq!(wei_to_gwei(U256::from(1u64)), "0.000000001");
+        assert_eq!(wei_to_gwei(U256::from(30_000_000_000u64)), "30");
+        assert_eq!(wei_to_eth(U256::from(1_230_000_000_000_000_000u64)), "1.23");
+        assert_eq!(wei_to_eth(U256::ZERO), "0");
+    }
+}
@@ -0,0 +1,354 @@
+//! Parallel historical backfill.
+
+use futures_util::stream::{self, StreamExt};
+use mev_africa_db::DbPool;
+use mev_africa_telemetry::Metrics;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{error, info};
+use crate::block_processor::BlockProcessor;
+use crate::checkpoint::{CheckpointStore, CheckpointStream};
+use crate::fetcher::{BlockData, BlockSource};
+use crate::rpc_client::RpcClient;
+
+/// Default number of concurrent fetch workers.
+pub const DEFAULT_BACKFILL_CONCURRENCY: usize = 8;
+
+/// How often backfill progress is logged.
+const PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Outcome of a backfill run.
+#[derive(Debug)]
+pub struct BackfillReport {
+    /// Highest block committed. Every requested block up to this one was stored.
+    pub last_committed: Option<u64>,
+    /// Number of blocks committed.
+    pub blocks_committed: u64,
+    /// Wall-clock duration of the run.
+    pub elapsed: Duration,
+    /// The block that stopped the run and why, if it did not complete.
+    pub failure: Option<(u64, anyhow::Error)>,
+    /// Whether the run stopped early because shutdown was requested.
+    pub interrupted: bool,
+}
+
+/// Backfills block ranges with concurrent fetches and in-order commits.
+///
+/// Blocks are requested in JSON-RPC batches by up to `concurrency` workers
+/// at once, then handed to the [`BlockProcessor`] strictly in ascending
+/// order. The run stops at the first block that cannot be fetched or stored,
+/// so everything up to [`BackfillReport::last_committed`] is contiguous and
+/// the run can be resumed from there.
+pub struct BackfillEngine<'a> {
+    fetcher: &'a dyn BlockSource,
+    processor: &'a BlockProcessor,
+    metrics: Metrics,
+    concurrency: usize,
+    batch_size: usize,
+    checkpoint: Option<(CheckpointStore, CheckpointStream)>,
+    shutdown: Option<watch::Receiver<bool>>,
+}
+
+impl<'a> BackfillEngine<'a> {
+    /// Create a new backfill engine.
+    ///
+    /// # Arguments
+    /// * `fetcher` - Block source, usually a [`BlockFetcher`](crate::fetcher::BlockFetcher)
+    /// * `processor` - Block processor used for storing blocks
+    /// * `metrics` - Metrics collector
+    /// * `concurrency` - Number of batches fetched concurrently
+    /// * `batch_size` - Number of blocks per JSON-RPC batch
+    pub fn new(
+        fetcher: &'a dyn BlockSource,
+        processor: &'a BlockProcessor,
+        metrics: Metrics,
+        concurrency: usize,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            fetcher,
+            processor,
+            metrics,
+            concurrency: concurrency.max(1),
+            batch_size: batch_size.max(1),
+            checkpoint: None,
+            shutdown: None,
+        }
+    }
+
+    /// Save a checkpoint for `stream` after every committed block.
+    pub fn with_checkpoint(mut self, store: CheckpointStore, stream: CheckpointStream) -> Self {
+        self.checkpoint = Some((store, stream));
+        self
+    }
+
+    /// Stop between blocks once `shutdown` turns true.
+    ///
+    /// The block being stored when shutdown is requested is still committed.
+    pub fn with_shutdown(mut self, shutdown: watch::Receiver<bool>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Backfill the given block numbers, which must be in ascending order.
+    pub async fn run(&self, block_numbers: &[u64]) -> BackfillReport {
+        let mut progress = Progress::new(block_numbers.len() as u64, self.metrics.clone());
+        let mut report = BackfillReport {
+            last_committed: None,
+            blocks_committed: 0,
+            elapsed: Duration::ZERO,
+            failure: None,
+            interrupted: false,
+        };
+
+        if let (Some(first), Some(last)) = (block_numbers.first(), block_numbers.last()) {
+            info!(
+                "Backfilling {} blocks from {} to {} with {} workers",
+                block_numbers.len(), first, last, self.concurrency
+            );
+        }
+
+        let mut batches = stream::iter(block_numbers.chunks(self.batch_size))
+            .map(|batch| async move { (batch, self.fetcher.fetch_blocks(batch).await) })
+            .buffered(self.concurrency);
+
+        'batches: while let Some((batch, fetched)) = batches.next().await {
+            let blocks = match fetched {
+                Ok(blocks) => blocks,
+                Err(e) => {
+                    report.failure = Some((batch[0], e));
+                    break;
+                }
+            };
+
+            for (&block_num, result) in batch.iter().zip(blocks) {
+                if self.shutdown.as_ref().is_some_and(|rx| *rx.borrow()) {
+                    report.interrupted = true;
+                    break 'batches;
+                }
+                if let Err(e) = self.commit(block_num, result).await {
+                    report.failure = Some((block_num, e));
+                    break 'batches;
+                }
+                report.last_committed = Some(block_num);
+                report.blocks_committed += 1;
+                progress.record(block_num);
+            }
+        }
+
+        report.elapsed = progress.finish();
+        match &report.failure {
+            Some((block_num, e)) => error!(
+                "Backfill stopped at block {} after {} blocks: {}",
+                block_num, report.blocks_committed, e
+            ),
+            None if report.interrupted => info!(
+                "Backfill interrupted by shutdown after {} blocks",
+                report.blocks_committed
+            ),
+            None => info!(
+                "Backfill completed: {} blocks in {:.1}s",
+                report.blocks_committed,
+                report.elapsed.as_secs_f64()
+            ),
+        }
+        report
+    }
+
+    async fn commit(&self, block_num: u64, fetched: anyhow::Result<Option<BlockData>>) -> anyhow::Result<()> {
+        let block_data = fetched?.ok_or_else(|| anyhow::anyhow!("Block {} not found", block_num))?;
+        self.processor.process_block(&block_data).await?;
+
+        if let Some((store, stream)) = &self.checkpoint {
+            store.save(*stream, block_num, Some(&block_data.hash())).await?;
+        }
+        Ok(())
+    }
+}
+
+/// List the block numbers in `from..=to` that are not stored yet.
+///
+/// Backfills only request these, so re-running an interrupted backfill
+/// over the same range resumes where it stopped.
+pub async fn missing_block_numbers(db: &DbPool, from: u64, to: u64) -> anyhow::Result<Vec<u64>> {
+    let stored: Vec<i64> = sqlx::query_scalar(
+        "SELECT block_number FROM blocks WHERE block_number BETWEEN ? AND ?"
+    )
+    .bind(from as i64)
+    .bind(to as i64)
+    .fetch_all(db.pool())
+    .await?;
+
+    let stored: HashSet<u64> = stored.into_iter().map(|n| n as u64).collect();
+    Ok((from..=to).filter(|n| !stored.contains(n)).collect())
+}
+
+/// Find the first block whose timestamp is at or after `timestamp`.
+///
+/// Binary search over `eth_getBlockByNumber` up to `head`. Returns
+/// `head + 1` when every block up to `head` is older than `timestamp`.
+///
+/// # Arguments
+/// * `rpc_client` - RPC client used to fetch block timestamps
+/// * `head` - Highest block number to consider
+/// * `timestamp` - Unix timestamp in seconds
+pub async fn first_block_at_or_after(
+    rpc_client: &RpcClient,
+    head: u64,
+    timestamp: u64,
+) -> anyhow::Result<u64> {
+    partition_point(0, head + 1, |block_num| async move {
+        let block_timestamp = rpc_client
+            .get_block_timestamp(block_num)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Block {} not found", block_num))?;
+        Ok(block_timestamp < timestamp)
+    })
+    .await
+}
+
+/// Return the first number in `lo..hi` for which `is_before` is false,
+/// assuming `is_before` is true for a prefix of the range and false after.
+async fn partition_point<F, Fut>(mut lo: u64, mut hi: u64, mut is_before: F) -> anyhow::Result<u64>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = anyhow::Result<bool>>,
+{
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if is_before(mid).await? {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(lo)
+}
+
+/// Throughput and ETA tracking for a backfill run.
+struct Progress {
+    total: u64,
+    done: u64,
+    started: Instant,
+    last_logged: Instant,
+    metrics: Metrics,
+}
+
+impl Progress {
+    fn new(total: u64, metrics: Metrics) -> Self {
+        metrics.set_backfill_progress(total, 0.0, 0.0);
+        let now = Instant::now();
+        Self {
+            total,
+            done: 0,
+            started: now,
+            last_logged: now,
+            metrics,
+        }
+    }
+
+    fn record(&mut self, block_num: u64) {
+        self.done += 1;
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let remaining = self.total - self.done;
+        let blocks_per_sec = if elapsed > 0.0 { self.done as f64 / elapsed } else { 0.0 };
+        let eta_secs = if blocks_per_sec > 0.0 { remaining as f64 / blocks_per_sec } else { 0.0 };
+        self.metrics.set_backfill_progress(remaining, blocks_per_sec, eta_secs);
+
+        if self.last_logged.elapsed() >= PROGRESS_LOG_INTERVAL {
+            self.last_logged = Instant::now();
+            info!(
+                "Backfill progress: block {}, {}/{} blocks, {:.1} blocks/sec, ETA {:.0}s",
+                block_num, self.done, self.total, blocks_per_sec, eta_secs
+            );
+        }
+    }
+
+    fn finish(&self) -> Duration {
+        self.metrics.set_backfill_progress(self.total - self.done, 0.0, 0.0);
+        self.started.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_db, test_metrics};
+    use crate::validator_tagger::ValidatorTagger;
+    use alloy::primitives::B256;
+    use alloy::rpc::types::{Block, BlockTransactions};
+    use async_trait::async_trait;
+
+    /// Serves empty blocks, answering later batches first. Fetching
+    /// `failing_block` fails.
+    struct MockFetcher {
+        failing_block: u64,
+    }
+
+    #[async_trait]
+    impl BlockSource for MockFetcher {
+        async fn fetch_blocks(&self, block_numbers: &[u64]) -> anyhow::Result<Vec<anyhow::Result<Option<BlockData>>>> {
+            tokio::time::sleep(Duration::from_millis(100 - 10 * block_numbers[0])).await;
+            Ok(block_numbers
+                .iter()
+                .map(|&number| {
+                    if number == self.failing_block {
+                        return Err(anyhow::anyhow!("Block {} unavailable", number));
+                    }
+                    let mut block: Block = Block::default();
+                    block.header.number = number;
+                    block.header.hash = B256::repeat_byte(number as u8);
+                    block.transactions = BlockTransactions::Full(vec![]);
+                    Ok(Some(BlockData::from_block(block)))
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backfill_commits_in_order_and_stops_at_first_failure() {
+        let db = test_db("backfill_engine").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+        let checkpoints = CheckpointStore::new(db.clone());
+        let fetcher = MockFetcher { failing_block: 6 };
+
+        let report = BackfillEngine::new(&fetcher, &processor, test_metrics(), 4, 2)
+            .with_checkpoint(checkpoints.clone(), CheckpointStream::Backfill)
+            .run(&(1..=8).collect::<Vec<u64>>())
+            .await;
+
+        assert_eq!(report.last_committed, Some(5));
+        assert_eq!(report.blocks_committed, 5);
+        assert_eq!(report.failure.as_ref().map(|(block, _)| *block), Some(6));
+        assert!(!report.interrupted);
+
+        // Blocks 7 and 8 arrived first but are never committed past the gap
+        let stored: Vec<i64> = sqlx::query_scalar("SELECT block_number FROM blocks ORDER BY id")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(stored, vec![1, 2, 3, 4, 5]);
+        let checkpoint = checkpoints.load(CheckpointStream::Backfill).await.unwrap();
+        assert_eq!(checkpoint.map(|(block, _)| block), Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_partition_point_finds_first_block_at_timestamp() {
+        // Synthetic chain with a 12 second slot time starting at t=1000
+        let timestamp_of = |n: u64| 1000 + 12 * n;
+        let search = |target: u64| {
+            partition_point(0, 101, move |n| async move { Ok(timestamp_of(n) < target) })
+        };
+
+        assert_eq!(search(0).await.unwrap(), 0);
+        assert_eq!(search(1000).await.unwrap(), 0);
+        assert_eq!(search(1001).await.unwrap(), 1);
+        assert_eq!(search(1012).await.unwrap(), 1);
+        assert_eq!(search(1000 + 12 * 100).await.unwrap(), 100);
+        assert_eq!(search(1000 + 12 * 100 + 1).await.unwrap(), 101);
+    }
+}
@@ -1,13 +1,25 @@
 //! Block processing and storage logic.
 
+use alloy::primitives::U256;
+use alloy::rpc::types::{Transaction, Withdrawal};
 use chrono::DateTime;
+use mev_africa_beacon::{BeaconAdapter, BeaconError, ProposerInfo, Slot, SlotClock};
+use mev_africa_db::models::{format_wei, MevReasonCode};
 use mev_africa_db::DbPool;
-use mev_africa_telemetry::{Metrics, audit};
-use rust_decimal::Decimal;
+use mev_africa_heuristics::TransactionAnalyzer;
+use mev_africa_telemetry::Metrics;
+use mev_africa_telemetry::audit::AuditWriter;
 use serde::Serialize;
-use serde_json::Value;
-use sqlx::Row;
-use tracing::{error, info, warn};
+use sqlx::{Row, SqliteConnection};
+use std::sync::Arc;
+use tracing::{info, warn};
+use crate::builder_identifier::{decode_extra_data, record_builder, BuilderIdentifier};
+use crate::fees::{burned_fees, effective_priority_fee, priority_fee_paid};
+use crate::fetcher::BlockData;
+use crate::finality::FinalityStatus;
+use crate::proposer_payment::detect_proposer_payment;
+use crate::receipts::ReceiptData;
+use crate::traces::TransactionTrace;
 use crate::validator_tagger::ValidatorTagger;
 
 /// Block processor for ingesting and storing blocks.
@@ -15,7 +27,11 @@ pub struct BlockProcessor {
     db: DbPool,
     metrics: Metrics,
     validator_tagger: ValidatorTagger,
-    sample_output_path: Option<String>,
+    builder_identifier: BuilderIdentifier,
+    beacon_adapter: Option<Arc<dyn BeaconAdapter>>,
+    slot_clock: SlotClock,
+    audit_writer: Option<AuditWriter>,
+    finality_status: FinalityStatus,
 }
 
 #[derive(Debug, Serialize)]
@@ -23,6 +39,7 @@ struct AuditBlock {
     block_number: u64,
     block_hash: String,
     fee_recipient: String,
+    proposer_payment_recipient: Option<String>,
     is_africa_tagged: bool,
     transaction_count: usize,
     mev_candidate_count: usize,
@@ -46,99 +63,236 @@ impl BlockProcessor {
             db,
             metrics,
             validator_tagger,
-            sample_output_path,
+            builder_identifier: BuilderIdentifier::new(),
+            beacon_adapter: None,
+            slot_clock: SlotClock::MAINNET,
+            audit_writer: sample_output_path.map(AuditWriter::new),
+            finality_status: FinalityStatus::Latest,
         }
     }
 
+    /// Write buffered audit samples to disk. Call before shutting down.
+    pub fn flush_audit(&self) -> anyhow::Result<()> {
+        match &self.audit_writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Set the finality status recorded for newly stored blocks.
+    ///
+    /// Use this when ingestion follows the `safe` or `finalized` head, so
+    /// stored blocks start out with the status they were fetched at.
+    pub fn with_finality_status(mut self, finality_status: FinalityStatus) -> Self {
+        self.finality_status = finality_status;
+        self
+    }
+
+    /// Use a builder identifier other than the built-in builder list.
+    pub fn with_builder_identifier(mut self, builder_identifier: BuilderIdentifier) -> Self {
+        self.builder_identifier = builder_identifier;
+        self
+    }
+
+    /// Look up the proposer of every proof-of-stake block with a beacon
+    /// adapter, and tag blocks whose proposer pubkey is an Africa validator.
+    pub fn with_beacon_adapter(mut self, beacon_adapter: Arc<dyn BeaconAdapter>) -> Self {
+        self.beacon_adapter = Some(beacon_adapter);
+        self
+    }
+
+    /// Set the clock used to derive slots from block timestamps. Defaults to mainnet.
+    pub fn with_slot_clock(mut self, slot_clock: SlotClock) -> Self {
+        self.slot_clock = slot_clock;
+        self
+    }
+
     /// Process and store a block.
     ///
+    /// Processing is idempotent: a block that is already stored with the same
+    /// hash is updated in place, keeping the primary keys of the block and its
+    /// transactions so annotations stay attached.
+    ///
+    /// The block row, all of its transactions with their receipts and logs,
+    /// and the builder update are written in a single database transaction,
+    /// so a block is either stored completely or not at all. Failures are
+    /// counted by kind in `mev_africa_block_write_failures_total`.
+    ///
+    /// Blocks fetched without receipts keep any receipt data already stored.
+    ///
     /// # Arguments
-    /// * `block_json` - The block JSON data from RPC
-    pub async fn process_block(&self, block_json: &Value) -> anyhow::Result<()> {
-        // Extract block fields from JSON
-        let block_number_hex = block_json["number"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Block missing number"))?;
-        let block_number = u64::from_str_radix(
-            block_number_hex.strip_prefix("0x").unwrap_or(block_number_hex),
-            16,
-        )?;
+    /// * `block_data` - The block fetched from RPC with its receipts and traces
+    pub async fn process_block(&self, block_data: &BlockData) -> anyhow::Result<()> {
+        let result = self.store_block(block_data).await;
+        if let Err(e) = &result {
+            self.metrics.inc_block_write_failures(failure_kind(e));
+        }
+        result
+    }
+
+    async fn store_block(&self, block_data: &BlockData) -> anyhow::Result<()> {
+        let header = &block_data.block.header;
+        let block_number = header.number;
 
         info!("Processing block {}", block_number);
 
-        let block_hash = block_json["hash"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Block missing hash"))?
-            .to_string();
-        let parent_hash = block_json["parentHash"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Block missing parentHash"))?
-            .to_string();
-        
-        let timestamp_hex = block_json["timestamp"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Block missing timestamp"))?;
-        let timestamp = DateTime::from_timestamp(
-            i64::from_str_radix(
-                timestamp_hex.strip_prefix("0x").unwrap_or(timestamp_hex),
-                16,
-            )?,
-            0,
-        ).ok_or_else(|| anyhow::anyhow!("Invalid timestamp"))?;
-
-        let fee_recipient = block_json["miner"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Block missing miner"))?
-            .to_string();
-        let is_africa_tagged = self.validator_tagger.is_africa_tagged(&fee_recipient);
-
-        let base_fee = block_json["baseFeePerGas"]
-            .as_str()
-            .map(|s| {
-                u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16)
-                    .unwrap_or(0)
-                    .to_string()
-            })
-            .unwrap_or_else(|| "0".to_string());
-
-        let gas_used_hex = block_json["gasUsed"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Block missing gasUsed"))?;
-        let gas_used = i64::from_str_radix(
-            gas_used_hex.strip_prefix("0x").unwrap_or(gas_used_hex),
-            16,
-        )?;
-
-        // Extract transactions
-        let transactions_json = block_json["transactions"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Block missing transactions array"))?;
-        
-        // For now, we'll process transactions as JSON and extract what we need
-        // Calculate total priority fees from transactions
-        let mut total_priority_fees = Decimal::ZERO;
-        let mut transactions_data = Vec::new();
-        
-        for tx_json in transactions_json {
-            if let Some(priority_fee_hex) = tx_json["maxPriorityFeePerGas"].as_str() {
-                if let Ok(priority_fee) = u64::from_str_radix(
-                    priority_fee_hex.strip_prefix("0x").unwrap_or(priority_fee_hex),
-                    16,
-                ) {
-                    total_priority_fees += Decimal::from(priority_fee);
+        let block_hash = block_data.hash();
+        let parent_hash = format!("{:#x}", header.parent_hash);
+        let timestamp = i64::try_from(header.timestamp)
+            .ok()
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+            .ok_or_else(|| anyhow::anyhow!("Invalid timestamp {}", header.timestamp))?;
+
+        let fee_recipient = format!("{:#x}", header.miner);
+        let extra_data = header.extra_data.to_string();
+        let extra_data_text = decode_extra_data(&header.extra_data);
+        let builder = self.builder_identifier.identify(&fee_recipient, extra_data_text.as_deref());
+
+        // Blocks before London have no base fee
+        let base_fee_per_gas = header.base_fee_per_gas;
+        let base_fee = format_wei(U256::from(base_fee_per_gas.unwrap_or(0)));
+        let gas_used = i64::try_from(header.gas_used)?;
+        let burned = burned_fees(base_fee_per_gas, header.gas_used);
+        let blob_gas_used = header.blob_gas_used.map(i64::try_from).transpose()?;
+        let excess_blob_gas = header.excess_blob_gas.map(i64::try_from).transpose()?;
+        let withdrawals_root = header.withdrawals_root.map(|root| format!("{:#x}", root));
+        let parent_beacon_block_root = header.parent_beacon_block_root.map(|root| format!("{:#x}", root));
+
+        let transactions_data = block_data
+            .block
+            .transactions
+            .as_transactions()
+            .ok_or_else(|| BlockRejected::Rpc(format!("Block {} has no full transactions", block_number)))?;
+
+        // Sum of the tips per gas actually paid, not the users' caps
+        let total_priority_fees: U256 = transactions_data
+            .iter()
+            .map(|tx| effective_priority_fee(tx, base_fee_per_gas))
+            .sum();
+
+        let receipts = block_data
+            .receipts
+            .as_ref()
+            .map(|receipts| {
+                if receipts.len() != transactions_data.len() {
+                    return Err(BlockRejected::Rpc(format!(
+                        "Block {} has {} transactions but {} receipts",
+                        block_number,
+                        transactions_data.len(),
+                        receipts.len()
+                    ))
+                    .into());
                 }
+                receipts.iter().map(ReceiptData::from_json).collect::<anyhow::Result<Vec<_>>>()
+            })
+            .transpose()?;
+        // What the fee recipient earned from tips, which needs each
+        // transaction's gas used
+        let priority_revenue: Option<U256> = receipts.as_ref().map(|receipts| {
+            transactions_data
+                .iter()
+                .zip(receipts)
+                .map(|(tx, receipt)| priority_fee_paid(tx, base_fee_per_gas, receipt.gas_used as u64))
+                .sum()
+        });
+
+        // In MEV-Boost blocks the fee recipient is the builder, so the
+        // proposer is only visible as the recipient of the builder's payment
+        let proposer_payment =
+            detect_proposer_payment(&block_data.block, receipts.as_ref().and_then(|receipts| receipts.last()));
+
+        // Proof-of-stake blocks have zero difficulty and start at a slot boundary
+        let slot = if header.difficulty.is_zero() {
+            self.slot_clock.slot_at(header.timestamp)
+        } else {
+            None
+        };
+        let proposer = match slot {
+            Some(slot) => self.fetch_proposer(slot).await,
+            None => None,
+        };
+
+        let is_africa_tagged = self.validator_tagger.is_africa_tagged(&fee_recipient)
+            || proposer_payment
+                .as_ref()
+                .is_some_and(|payment| self.validator_tagger.is_africa_tagged(&payment.recipient))
+            || proposer
+                .as_ref()
+                .is_some_and(|proposer| self.validator_tagger.is_africa_pubkey(&proposer.pubkey));
+
+        if let Some(traces) = &block_data.traces {
+            if traces.len() != transactions_data.len() {
+                return Err(BlockRejected::Rpc(format!(
+                    "Block {} has {} transactions but {} traces",
+                    block_number,
+                    transactions_data.len(),
+                    traces.len()
+                ))
+                .into());
             }
-            transactions_data.push(tx_json.clone());
         }
 
-        // Store block
+        let mut db_tx = self.db.pool().begin().await?;
+
+        // A different block at this height means a reorg that has not been
+        // rolled back yet; never overwrite it silently.
+        let stored_hash: Option<String> = sqlx::query_scalar(
+            "SELECT block_hash FROM blocks WHERE block_number = ?"
+        )
+        .bind(block_number as i64)
+        .fetch_optional(&mut *db_tx)
+        .await?;
+        if let Some(stored_hash) = stored_hash.filter(|h| !h.eq_ignore_ascii_case(&block_hash)) {
+            return Err(BlockRejected::Conflict {
+                block_number,
+                stored_hash,
+                block_hash,
+            }
+            .into());
+        }
+
+        // Store block. Reprocessing updates the row in place, keeping its id
+        // (and therefore its annotations) and never demoting its finality.
         let block_id = sqlx::query(
             r#"
             INSERT INTO blocks (
                 block_number, block_hash, parent_hash, timestamp,
                 fee_recipient, base_fee, gas_used, total_priority_fees,
-                is_africa_tagged
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                burned_fees, priority_revenue, blob_gas_used, excess_blob_gas,
+                withdrawals_root, parent_beacon_block_root, extra_data, extra_data_text,
+                builder_name, builder_confidence, proposer_payment_recipient,
+                proposer_payment_amount, proposer_payment_tx_hash, slot, proposer_index,
+                proposer_pubkey, is_africa_tagged, finality_status
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(block_number) DO UPDATE SET
+                parent_hash = excluded.parent_hash,
+                timestamp = excluded.timestamp,
+                fee_recipient = excluded.fee_recipient,
+                base_fee = excluded.base_fee,
+                gas_used = excluded.gas_used,
+                total_priority_fees = excluded.total_priority_fees,
+                burned_fees = excluded.burned_fees,
+                priority_revenue = COALESCE(excluded.priority_revenue, blocks.priority_revenue),
+                blob_gas_used = excluded.blob_gas_used,
+                excess_blob_gas = excluded.excess_blob_gas,
+                withdrawals_root = excluded.withdrawals_root,
+                parent_beacon_block_root = excluded.parent_beacon_block_root,
+                extra_data = excluded.extra_data,
+                extra_data_text = excluded.extra_data_text,
+                builder_name = excluded.builder_name,
+                builder_confidence = excluded.builder_confidence,
+                proposer_payment_recipient = excluded.proposer_payment_recipient,
+                proposer_payment_amount = excluded.proposer_payment_amount,
+                proposer_payment_tx_hash = excluded.proposer_payment_tx_hash,
+                slot = excluded.slot,
+                proposer_index = COALESCE(excluded.proposer_index, blocks.proposer_index),
+                proposer_pubkey = COALESCE(excluded.proposer_pubkey, blocks.proposer_pubkey),
+                is_africa_tagged = excluded.is_africa_tagged,
+                finality_status = CASE
+                    WHEN 'finalized' IN (blocks.finality_status, excluded.finality_status) THEN 'finalized'
+                    WHEN 'safe' IN (blocks.finality_status, excluded.finality_status) THEN 'safe'
+                    ELSE 'latest'
+                END
             RETURNING id
             "#,
         )
@@ -149,29 +303,58 @@ impl BlockProcessor {
         .bind(&fee_recipient)
         .bind(&base_fee)
         .bind(gas_used)
-        .bind(total_priority_fees.to_string())
+        .bind(format_wei(total_priority_fees))
+        .bind(format_wei(burned))
+        .bind(priority_revenue.map(format_wei))
+        .bind(blob_gas_used)
+        .bind(excess_blob_gas)
+        .bind(withdrawals_root.as_ref())
+        .bind(parent_beacon_block_root.as_ref())
+        .bind(&extra_data)
+        .bind(extra_data_text.as_ref())
+        .bind(builder.as_ref().map(|b| &b.name))
+        .bind(builder.as_ref().map(|b| b.confidence.as_str()))
+        .bind(proposer_payment.as_ref().map(|p| &p.recipient))
+        .bind(proposer_payment.as_ref().map(|p| format_wei(p.amount)))
+        .bind(proposer_payment.as_ref().map(|p| &p.tx_hash))
+        .bind(slot.map(i64::try_from).transpose()?)
+        .bind(proposer.as_ref().map(|p| i64::try_from(p.index)).transpose()?)
+        .bind(proposer.as_ref().map(|p| p.pubkey.to_lowercase()))
         .bind(is_africa_tagged)
-        .fetch_one(self.db.pool())
+        .bind(self.finality_status.as_str())
+        .fetch_one(&mut *db_tx)
         .await?
         .get::<i64, _>(0);
 
         // Process transactions
+        let block_txs: Vec<&Transaction> = transactions_data.iter().collect();
         let mut mev_candidate_count = 0;
-        for (index, tx_json) in transactions_data.iter().enumerate() {
-            match self.process_transaction_json(block_id, tx_json, &transactions_data, index).await {
-                Ok(is_mev) => {
-                    if is_mev {
-                        mev_candidate_count += 1;
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to process transaction {} in block {}: {}", index, block_number, e);
-                }
+        for index in 0..block_txs.len() {
+            let receipt = receipts.as_ref().map(|receipts| &receipts[index]);
+            let trace = block_data.traces.as_ref().map(|traces| &traces[index]);
+            let (transaction_id, is_mev) = self
+                .process_transaction(&mut db_tx, block_id, &block_txs, index, base_fee_per_gas, receipt)
+                .await
+                .map_err(|e| e.context(format!("Transaction {} in block {}", index, block_number)))?;
+            if let Some(receipt) = receipt {
+                self.store_logs(&mut db_tx, transaction_id, receipt).await?;
+            }
+            if let Some(trace) = trace {
+                self.store_trace(&mut db_tx, transaction_id, trace).await?;
             }
+            if is_mev {
+                mev_candidate_count += 1;
+            }
+        }
+
+        if let Some(withdrawals) = &block_data.block.withdrawals {
+            self.store_withdrawals(&mut db_tx, block_id, withdrawals).await?;
         }
 
         // Update builder table
-        self.update_builder(&fee_recipient).await?;
+        record_builder(&mut db_tx, &fee_recipient, builder.as_ref()).await?;
+
+        db_tx.commit().await?;
 
         // Update metrics
         self.metrics.inc_blocks_processed();
@@ -186,13 +369,14 @@ impl BlockProcessor {
             block_number,
             block_hash,
             fee_recipient,
+            proposer_payment_recipient: proposer_payment.map(|p| p.recipient),
             is_africa_tagged,
             transaction_count: transactions_data.len(),
             mev_candidate_count: mev_candidate_count as usize,
         };
 
-        if let Some(ref path) = self.sample_output_path {
-            if let Err(e) = audit::write_audit_sample(Some(path), &audit_block) {
+        if let Some(ref writer) = self.audit_writer {
+            if let Err(e) = writer.write(&audit_block) {
                 warn!("Failed to write audit sample: {}", e);
             }
         }
@@ -208,124 +392,68 @@ impl BlockProcessor {
         Ok(())
     }
 
-    async fn process_transaction_json(
+    async fn process_transaction(
         &self,
+        conn: &mut SqliteConnection,
         block_id: i64,
-        tx_json: &Value,
-        block_txs: &[Value],
+        block_txs: &[&Transaction],
         tx_index: usize,
-    ) -> anyhow::Result<bool> {
-        // Extract transaction data from JSON
-        let tx_hash = tx_json["hash"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Transaction missing hash"))?
-            .to_string();
+        base_fee: Option<u64>,
+        receipt: Option<&ReceiptData>,
+    ) -> anyhow::Result<(i64, bool)> {
+        let tx = block_txs[tx_index];
+        let tx_hash = format!("{:#x}", tx.hash);
         let position_index = tx_index as i64;
-        let sender_address = tx_json["from"]
-            .as_str()
-            .unwrap_or("unknown")
-            .to_string();
-
-        let max_priority_fee = tx_json["maxPriorityFeePerGas"]
-            .as_str()
-            .map(|s| {
-                u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16)
-                    .unwrap_or(0)
-                    .to_string()
-            })
-            .unwrap_or_else(|| "0".to_string());
-
-        // MEV detection heuristics
-        let mut mev_reasons = Vec::new();
-        
-        // 1. High priority fee outlier (check if >3x block median)
-        let priority_fee_value = max_priority_fee.parse::<u64>().unwrap_or(0);
-        if priority_fee_value > 0 {
-            // Calculate median priority fee for the block
-            let mut fees: Vec<u64> = block_txs
-                .iter()
-                .filter_map(|tx| {
-                    tx["maxPriorityFeePerGas"]
-                        .as_str()
-                        .and_then(|s| u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).ok())
-                })
-                .collect();
-            
-            if !fees.is_empty() {
-                fees.sort();
-                let median = if fees.len() % 2 == 0 {
-                    (fees[fees.len() / 2 - 1] + fees[fees.len() / 2]) / 2
-                } else {
-                    fees[fees.len() / 2]
-                };
-                
-                if priority_fee_value > median * 3 && median > 0 {
-                    mev_reasons.push("high_priority_fee_outlier");
-                }
-            }
-        }
-        
-        // 2. Repeated sender (check if sender appears 3+ times in block)
-        let sender_count = block_txs
-            .iter()
-            .filter(|tx| tx["from"].as_str() == tx_json["from"].as_str())
-            .count();
-        if sender_count >= 3 {
-            mev_reasons.push("repeated_sender_sequence");
-        }
-        
-        // 3. Atomic multiswap (check for multiple swap patterns in calldata)
-        if let Some(input) = tx_json["input"].as_str() {
-            let swap_patterns = ["022c0d9f", "472b43f3", "5c11d795", "7ff36ab5", "414bf389"];
-            let pattern_count = swap_patterns
-                .iter()
-                .filter(|pattern| input.contains(*pattern))
-                .count();
-            if pattern_count >= 2 {
-                mev_reasons.push("atomic_multiswap");
-            }
-        }
-        
-        // 4. Sandwich pattern (same sender before and after this tx)
-        let tx_sender = tx_json["from"].as_str();
-        if let Some(sender) = tx_sender {
-            let has_before = block_txs[..tx_index]
-                .iter()
-                .any(|tx| tx["from"].as_str() == Some(sender));
-            let has_after = block_txs[tx_index + 1..]
-                .iter()
-                .any(|tx| tx["from"].as_str() == Some(sender));
-            if has_before && has_after {
-                mev_reasons.push("sandwich_pattern");
-            }
-        }
-        
-        let is_mev_candidate = !mev_reasons.is_empty();
+        let sender_address = format!("{:#x}", tx.from);
+
+        let max_priority_fee = format_wei(U256::from(tx.max_priority_fee_per_gas.unwrap_or(0)));
+        let value = format_wei(tx.value);
+        let effective_priority_fee = format_wei(effective_priority_fee(tx, base_fee));
+        let max_fee_per_blob_gas = tx.max_fee_per_blob_gas.map(|fee| format_wei(U256::from(fee)));
+        let blob_hash_count = tx.blob_versioned_hashes.as_ref().map(|hashes| hashes.len() as i64);
+        let priority_fee_paid = receipt.map(|r| format_wei(priority_fee_paid(tx, base_fee, r.gas_used as u64)));
+
+        let analysis = TransactionAnalyzer::analyze(tx, block_txs, tx_index);
+        let is_mev_candidate = analysis.is_mev_candidate;
         let mev_reason_codes = if is_mev_candidate {
-            Some(serde_json::to_string(&mev_reasons)?)
+            let codes: Vec<&str> = analysis.reason_codes.iter().map(MevReasonCode::as_str).collect();
+            Some(serde_json::to_string(&codes)?)
         } else {
             None
         };
-        
-        // Extract calldata summary
-        let calldata_summary = tx_json["input"]
-            .as_str()
-            .map(|input| {
-                if input.len() > 200 {
-                    format!("{}...", &input[..200])
-                } else {
-                    input.to_string()
-                }
-            });
 
-        // Store transaction
-        sqlx::query(
+        // Store transaction. Receipt columns are only overwritten when this
+        // run fetched a receipt.
+        let transaction_id = sqlx::query(
             r#"
             INSERT INTO transactions (
                 block_id, tx_hash, position_index, sender_address,
-                max_priority_fee, calldata_summary, log_summary,
-                is_mev_candidate, mev_reason_codes
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                max_priority_fee, value, calldata_summary, log_summary,
+                is_mev_candidate, mev_reason_codes,
+                status, gas_used, effective_gas_price, contract_address,
+                effective_priority_fee, priority_fee_paid,
+                transaction_type, max_fee_per_blob_gas, blob_hash_count
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(tx_hash) DO UPDATE SET
+                block_id = excluded.block_id,
+                position_index = excluded.position_index,
+                sender_address = excluded.sender_address,
+                max_priority_fee = excluded.max_priority_fee,
+                value = excluded.value,
+                calldata_summary = excluded.calldata_summary,
+                log_summary = COALESCE(excluded.log_summary, transactions.log_summary),
+                is_mev_candidate = excluded.is_mev_candidate,
+                mev_reason_codes = excluded.mev_reason_codes,
+                status = COALESCE(excluded.status, transactions.status),
+                gas_used = COALESCE(excluded.gas_used, transactions.gas_used),
+                effective_gas_price = COALESCE(excluded.effective_gas_price, transactions.effective_gas_price),
+                contract_address = COALESCE(excluded.contract_address, transactions.contract_address),
+                effective_priority_fee = excluded.effective_priority_fee,
+                priority_fee_paid = COALESCE(excluded.priority_fee_paid, transactions.priority_fee_paid),
+                transaction_type = excluded.transaction_type,
+                max_fee_per_blob_gas = excluded.max_fee_per_blob_gas,
+                blob_hash_count = excluded.blob_hash_count
+            RETURNING id
             "#,
         )
         .bind(block_id)
@@ -333,32 +461,190 @@ impl BlockProcessor {
         .bind(position_index)
         .bind(&sender_address)
         .bind(&max_priority_fee)
-        .bind(calldata_summary.as_ref())
-        .bind(None::<String>)
+        .bind(&value)
+        .bind(analysis.calldata_summary.as_ref())
+        .bind(receipt.map(ReceiptData::log_summary))
         .bind(is_mev_candidate)
         .bind(mev_reason_codes.as_ref())
-        .execute(self.db.pool())
-        .await?;
+        .bind(receipt.and_then(|r| r.status))
+        .bind(receipt.map(|r| r.gas_used))
+        .bind(receipt.and_then(|r| r.effective_gas_price.as_ref()))
+        .bind(receipt.and_then(|r| r.contract_address.as_ref()))
+        .bind(&effective_priority_fee)
+        .bind(&priority_fee_paid)
+        .bind(tx.transaction_type.map(i64::from))
+        .bind(&max_fee_per_blob_gas)
+        .bind(blob_hash_count)
+        .fetch_one(&mut *conn)
+        .await?
+        .get::<i64, _>(0);
 
-        Ok(is_mev_candidate)
+        Ok((transaction_id, is_mev_candidate))
     }
 
-    async fn update_builder(&self, fee_recipient: &str) -> anyhow::Result<()> {
-        // Check if builder exists
-        let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM builders WHERE fee_recipient = ?)"
-        )
-        .bind(fee_recipient)
-        .fetch_one(self.db.pool())
-        .await?;
+    /// Look up the proposer of a slot. Beacon failures are logged and the
+    /// block is stored without its proposer rather than held back.
+    async fn fetch_proposer(&self, slot: Slot) -> Option<ProposerInfo> {
+        let adapter = self.beacon_adapter.as_ref()?;
+        let result = match adapter.get_proposer_for_slot(slot).await {
+            Ok(proposer) if proposer.pubkey.is_empty() => adapter
+                .get_validator_pubkey(proposer.index)
+                .await
+                .map(|pubkey| ProposerInfo { pubkey, ..proposer }),
+            result => result,
+        };
+
+        match result {
+            Ok(proposer) => Some(proposer),
+            Err(BeaconError::NotAvailable) => None,
+            Err(e) => {
+                warn!("Failed to get the proposer of slot {}: {}", slot, e);
+                None
+            }
+        }
+    }
+
+    /// Replace the stored withdrawals of a block.
+    async fn store_withdrawals(
+        &self,
+        conn: &mut SqliteConnection,
+        block_id: i64,
+        withdrawals: &[Withdrawal],
+    ) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM withdrawals WHERE block_id = ?")
+            .bind(block_id)
+            .execute(&mut *conn)
+            .await?;
+
+        for withdrawal in withdrawals {
+            sqlx::query(
+                r#"
+                INSERT INTO withdrawals (
+                    block_id, withdrawal_index, validator_index, address, amount
+                ) VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(block_id)
+            .bind(i64::try_from(withdrawal.index)?)
+            .bind(i64::try_from(withdrawal.validator_index)?)
+            .bind(format!("{:#x}", withdrawal.address))
+            .bind(format_wei(withdrawal.amount_wei()))
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the stored logs of a transaction with those in its receipt.
+    async fn store_logs(
+        &self,
+        conn: &mut SqliteConnection,
+        transaction_id: i64,
+        receipt: &ReceiptData,
+    ) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM logs WHERE transaction_id = ?")
+            .bind(transaction_id)
+            .execute(&mut *conn)
+            .await?;
+
+        for log in &receipt.logs {
+            sqlx::query(
+                r#"
+                INSERT INTO logs (
+                    transaction_id, log_index, address,
+                    topic0, topic1, topic2, topic3, data
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(transaction_id)
+            .bind(log.log_index)
+            .bind(&log.address)
+            .bind(log.topics.first())
+            .bind(log.topics.get(1))
+            .bind(log.topics.get(2))
+            .bind(log.topics.get(3))
+            .bind(&log.data)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the stored internal calls, transfers and failed subcalls of a
+    /// transaction with those in its trace.
+    async fn store_trace(
+        &self,
+        conn: &mut SqliteConnection,
+        transaction_id: i64,
+        trace: &TransactionTrace,
+    ) -> anyhow::Result<()> {
+        for table in ["internal_calls", "internal_transfers", "failed_subcalls"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE transaction_id = ?", table))
+                .bind(transaction_id)
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        for call in &trace.calls {
+            sqlx::query(
+                r#"
+                INSERT INTO internal_calls (
+                    transaction_id, trace_address, depth, call_type, from_address,
+                    to_address, value, gas_used, selector, error, reverted
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(transaction_id)
+            .bind(call.trace_address_string())
+            .bind(call.depth() as i64)
+            .bind(&call.call_type)
+            .bind(&call.from)
+            .bind(&call.to)
+            .bind(&call.value)
+            .bind(call.gas_used)
+            .bind(&call.selector)
+            .bind(&call.error)
+            .bind(call.reverted)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        for transfer in trace.transfers() {
+            sqlx::query(
+                r#"
+                INSERT INTO internal_transfers (
+                    transaction_id, trace_address, from_address, to_address, value
+                ) VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(transaction_id)
+            .bind(transfer.trace_address_string())
+            .bind(&transfer.from)
+            .bind(&transfer.to)
+            .bind(&transfer.value)
+            .execute(&mut *conn)
+            .await?;
+        }
 
-        if !exists {
-            // Insert as unknown builder
+        for call in trace.failed_calls() {
             sqlx::query(
-                "INSERT OR IGNORE INTO builders (fee_recipient, is_known) VALUES (?, 0)"
+                r#"
+                INSERT INTO failed_subcalls (
+                    transaction_id, trace_address, call_type, from_address,
+                    to_address, selector, error
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
             )
-            .bind(fee_recipient)
-            .execute(self.db.pool())
+            .bind(transaction_id)
+            .bind(call.trace_address_string())
+            .bind(&call.call_type)
+            .bind(&call.from)
+            .bind(&call.to)
+            .bind(&call.selector)
+            .bind(&call.error)
+            .execute(&mut *conn)
             .await?;
         }
 
@@ -366,3 +652,431 @@ impl BlockProcessor {
     }
 }
 
+/// Why a block was rejected before anything was written.
+#[derive(Debug, thiserror::Error)]
+pub enum BlockRejected {
+    /// A different block is already stored at this height
+    #[error("Block {block_number} is already stored with hash {stored_hash} but RPC returned {block_hash}")]
+    Conflict {
+        block_number: u64,
+        stored_hash: String,
+        block_hash: String,
+    },
+    /// The RPC responses for the block do not fit together
+    #[error("{0}")]
+    Rpc(String),
+}
+
+/// Classify a block write failure for the `kind` metric label.
+///
+/// * `conflict` - a different block is already stored at this height
+/// * `rpc` - the RPC returned incomplete or mismatched block data
+/// * `decode` - the block JSON was missing or had malformed fields
+/// * `constraint` - a UNIQUE, FOREIGN KEY or CHECK constraint was violated
+/// * `database` - any other database error (locked, I/O, pool timeout)
+fn failure_kind(error: &anyhow::Error) -> &'static str {
+    for cause in error.chain() {
+        if let Some(rejected) = cause.downcast_ref::<BlockRejected>() {
+            return match rejected {
+                BlockRejected::Conflict { .. } => "conflict",
+                BlockRejected::Rpc(_) => "rpc",
+            };
+        }
+        if let Some(sqlx_error) = cause.downcast_ref::<sqlx::Error>() {
+            return match sqlx_error {
+                sqlx::Error::Database(db_error) => match db_error.kind() {
+                    sqlx::error::ErrorKind::UniqueViolation
+                    | sqlx::error::ErrorKind::ForeignKeyViolation
+                    | sqlx::error::ErrorKind::NotNullViolation
+                    | sqlx::error::ErrorKind::CheckViolation => "constraint",
+                    _ => "database",
+                },
+                _ => "database",
+            };
+        }
+    }
+    "decode"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_db, test_metrics};
+    use alloy::primitives::{Address, B256};
+    use alloy::rpc::types::{Block, BlockTransactions, Header};
+    use serde_json::json;
+
+    fn test_block() -> Block {
+        Block {
+            header: Header {
+                number: 16,
+                hash: B256::repeat_byte(0x16),
+                parent_hash: B256::repeat_byte(0x15),
+                timestamp: 0x65920080,
+                miner: Address::repeat_byte(0x95),
+                base_fee_per_gas: Some(1_000_000_000),
+                gas_used: 21_000,
+                ..Default::default()
+            },
+            transactions: BlockTransactions::Full(vec![Transaction {
+                hash: B256::repeat_byte(0x01),
+                from: Address::repeat_byte(0x11),
+                max_fee_per_gas: Some(3_000_000_000),
+                max_priority_fee_per_gas: Some(1_000_000_000),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reprocessing_keeps_ids_and_annotations() {
+        let db = test_db("processor_idempotent").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        processor.process_block(&BlockData::from_block(test_block())).await.unwrap();
+        let (block_id, tx_id): (i64, i64) = sqlx::query_as(
+            "SELECT b.id, t.id FROM blocks b JOIN transactions t ON t.block_id = b.id"
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO annotations (transaction_id, tag) VALUES (?, 'reviewed')")
+            .bind(tx_id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        processor.process_block(&BlockData::from_block(test_block())).await.unwrap();
+        let ids: (i64, i64) = sqlx::query_as(
+            "SELECT b.id, t.id FROM blocks b JOIN transactions t ON t.block_id = b.id"
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(ids, (block_id, tx_id));
+
+        let annotations: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM annotations")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(annotations, 1);
+
+        // A different block at the same height is a reorg, not a reprocess
+        let mut reorged = test_block();
+        reorged.header.hash = B256::repeat_byte(0x99);
+        let error = processor.process_block(&BlockData::from_block(reorged)).await.unwrap_err();
+        assert_eq!(failure_kind(&error), "conflict");
+    }
+
+    #[tokio::test]
+    async fn test_failed_transaction_insert_leaves_no_rows() {
+        let db = test_db("processor_atomic").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        // Fail the second transaction insert, after the block row, the
+        // first transaction and its logs have been written
+        sqlx::query(
+            "CREATE TRIGGER fail_second_transaction BEFORE INSERT ON transactions
+             WHEN NEW.position_index = 1
+             BEGIN SELECT RAISE(ABORT, 'injected failure'); END"
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let mut block = test_block();
+        block.transactions = BlockTransactions::Full(vec![
+            Transaction {
+                hash: B256::repeat_byte(0x01),
+                from: Address::repeat_byte(0x11),
+                ..Default::default()
+            },
+            Transaction {
+                hash: B256::repeat_byte(0x02),
+                from: Address::repeat_byte(0x12),
+                ..Default::default()
+            },
+        ]);
+        let receipt = json!({
+            "status": "0x1",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x77359400",
+            "logs": [
+                {"logIndex": "0x0", "address": "0xtoken", "topics": ["0xsig"], "data": "0x"}
+            ]
+        });
+        let block_data = BlockData {
+            block,
+            receipts: Some(vec![receipt.clone(), receipt]),
+            traces: None,
+        };
+
+        let error = processor.process_block(&block_data).await.unwrap_err();
+        assert_eq!(failure_kind(&error), "database");
+
+        for table in ["blocks", "transactions", "logs"] {
+            let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+            assert_eq!(rows, 0, "{} rows left behind", table);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_receipts_are_rpc_failures() {
+        let db = test_db("processor_receipt_count").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        let block_data = BlockData {
+            block: test_block(),
+            receipts: Some(vec![]),
+            traces: None,
+        };
+        let error = processor.process_block(&block_data).await.unwrap_err();
+        assert_eq!(failure_kind(&error), "rpc");
+    }
+
+    #[tokio::test]
+    async fn test_receipts_are_kept_when_reprocessed_without_them() {
+        let db = test_db("processor_receipts").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        let receipt = json!({
+            "status": "0x0",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x77359400",
+            "logs": [
+                {"logIndex": "0x0", "address": "0xtoken", "topics": ["0xsig", "0xfrom"], "data": "0x"}
+            ]
+        });
+        let with_receipts = BlockData {
+            block: test_block(),
+            receipts: Some(vec![receipt]),
+            traces: None,
+        };
+        processor.process_block(&with_receipts).await.unwrap();
+        processor.process_block(&with_receipts).await.unwrap();
+        processor.process_block(&BlockData::from_block(test_block())).await.unwrap();
+
+        let (status, gas_used, price, summary): (Option<i64>, Option<i64>, Option<String>, Option<String>) =
+            sqlx::query_as("SELECT status, gas_used, effective_gas_price, log_summary FROM transactions")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(status, Some(0));
+        assert_eq!(gas_used, Some(21_000));
+        assert_eq!(price.as_deref(), Some("2000000000"));
+        assert_eq!(summary.as_deref(), Some(r#"{"count":1,"topics":["0xsig"]}"#));
+
+        let logs: Vec<(String, Option<String>, Option<String>)> =
+            sqlx::query_as("SELECT address, topic0, topic1 FROM logs")
+                .fetch_all(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(logs, vec![("0xtoken".to_string(), Some("0xsig".to_string()), Some("0xfrom".to_string()))]);
+
+        // 1 gwei tip and 1 gwei base fee over 21000 gas
+        let tip_paid: Option<String> = sqlx::query_scalar("SELECT priority_fee_paid FROM transactions")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(tip_paid.as_deref(), Some("21000000000000"));
+        let (burned, revenue): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT burned_fees, priority_revenue FROM blocks")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(burned.as_deref(), Some("21000000000000"));
+        assert_eq!(revenue.as_deref(), Some("21000000000000"));
+    }
+
+    #[tokio::test]
+    async fn test_reason_codes_come_from_heuristics_crate() {
+        let db = test_db("processor_reason_codes").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        let bot = Address::repeat_byte(0xb0);
+        let transactions = (0..3u8)
+            .map(|i| Transaction {
+                hash: B256::repeat_byte(i + 1),
+                from: bot,
+                max_priority_fee_per_gas: Some(1_000_000_000),
+                ..Default::default()
+            })
+            .collect();
+        let mut block = test_block();
+        block.transactions = BlockTransactions::Full(transactions);
+        processor.process_block(&BlockData::from_block(block)).await.unwrap();
+
+        let codes: Vec<String> = sqlx::query_scalar(
+            "SELECT mev_reason_codes FROM transactions ORDER BY position_index"
+        )
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(codes[0], r#"["repeated_sender"]"#);
+        assert_eq!(codes[1], r#"["repeated_sender","sandwich_pattern"]"#);
+    }
+
+    #[tokio::test]
+    async fn test_blob_and_withdrawal_fields_are_stored() {
+        let db = test_db("processor_blobs").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        let mut block = test_block();
+        block.header.blob_gas_used = Some(262_144);
+        block.header.excess_blob_gas = Some(0);
+        block.header.parent_beacon_block_root = Some(B256::repeat_byte(0xbe));
+        block.transactions = BlockTransactions::Full(vec![Transaction {
+            hash: B256::repeat_byte(0x01),
+            transaction_type: Some(3),
+            max_fee_per_gas: Some(3_000_000_000),
+            max_priority_fee_per_gas: Some(1_000_000_000),
+            max_fee_per_blob_gas: Some(1),
+            blob_versioned_hashes: Some(vec![B256::repeat_byte(0x01), B256::repeat_byte(0x02)]),
+            ..Default::default()
+        }]);
+        block.withdrawals = Some(vec![Withdrawal {
+            index: 7,
+            validator_index: 42,
+            address: Address::repeat_byte(0x95),
+            amount: 16_000_000, // Gwei
+        }]);
+        processor.process_block(&BlockData::from_block(block.clone())).await.unwrap();
+        processor.process_block(&BlockData::from_block(block)).await.unwrap();
+
+        let (blob_gas_used, beacon_root): (Option<i64>, Option<String>) =
+            sqlx::query_as("SELECT blob_gas_used, parent_beacon_block_root FROM blocks")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(blob_gas_used, Some(262_144));
+        assert_eq!(beacon_root, Some(format!("{:#x}", B256::repeat_byte(0xbe))));
+
+        let (tx_type, blob_fee, blob_count): (Option<i64>, Option<String>, Option<i64>) =
+            sqlx::query_as("SELECT transaction_type, max_fee_per_blob_gas, blob_hash_count FROM transactions")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!((tx_type, blob_fee.as_deref(), blob_count), (Some(3), Some("1"), Some(2)));
+
+        let withdrawals: Vec<(i64, i64, String)> =
+            sqlx::query_as("SELECT withdrawal_index, validator_index, amount FROM withdrawals")
+                .fetch_all(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(withdrawals, vec![(7, 42, "16000000000000000".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_builder_is_identified_from_extra_data() {
+        let db = test_db("processor_builders").await;
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        let mut block = test_block();
+        block.header.extra_data = b"beaverbuild.org".to_vec().into();
+        processor.process_block(&BlockData::from_block(block)).await.unwrap();
+
+        let (extra_data, text, builder, confidence): (Option<String>, Option<String>, Option<String>, Option<String>) =
+            sqlx::query_as("SELECT extra_data, extra_data_text, builder_name, builder_confidence FROM blocks")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(extra_data.as_deref(), Some("0x6265617665726275696c642e6f7267"));
+        assert_eq!(text.as_deref(), Some("beaverbuild.org"));
+        assert_eq!(builder.as_deref(), Some("beaverbuild"));
+        assert_eq!(confidence.as_deref(), Some("medium"));
+
+        let (name, is_known): (Option<String>, bool) =
+            sqlx::query_as("SELECT builder_name, is_known FROM builders")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(name.as_deref(), Some("beaverbuild"));
+        assert!(is_known);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_are_tagged_by_proposer_payment_recipient() {
+        let db = test_db("processor_proposer_payment").await;
+        let proposer = Address::repeat_byte(0xaf);
+        sqlx::query("INSERT INTO validators (fee_recipient, validator_pubkey) VALUES (?, '0xpubkey')")
+            .bind(format!("{:#x}", proposer))
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None);
+
+        let mut block = test_block();
+        block.transactions = BlockTransactions::Full(vec![Transaction {
+            hash: B256::repeat_byte(0x01),
+            from: block.header.miner,
+            to: Some(proposer),
+            value: U256::from(50_000_000_000_000_000u64),
+            ..Default::default()
+        }]);
+        processor.process_block(&BlockData::from_block(block)).await.unwrap();
+
+        let (recipient, amount, tagged): (Option<String>, Option<String>, bool) = sqlx::query_as(
+            "SELECT proposer_payment_recipient, proposer_payment_amount, is_africa_tagged FROM blocks"
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(recipient, Some(format!("{:#x}", proposer)));
+        assert_eq!(amount.as_deref(), Some("50000000000000000"));
+        assert!(tagged);
+    }
+
+    struct FixedProposer;
+
+    #[async_trait::async_trait]
+    impl BeaconAdapter for FixedProposer {
+        async fn get_proposer_for_slot(&self, slot: Slot) -> mev_africa_beacon::BeaconResult<ProposerInfo> {
+            Ok(ProposerInfo {
+                index: slot + 1,
+                pubkey: String::new(),
+            })
+        }
+
+        async fn get_validator_pubkey(&self, _proposer_index: u64) -> mev_africa_beacon::BeaconResult<String> {
+            Ok("0xAFRICA".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocks_are_tagged_by_proposer_pubkey() {
+        let db = test_db("processor_proposer_pubkey").await;
+        // The validator shares its fee recipient with a pool, so only the pubkey matches
+        sqlx::query("INSERT INTO validators (fee_recipient, validator_pubkey) VALUES ('0xpool', '0xafrica')")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let tagger = ValidatorTagger::new(&db).await.unwrap();
+        let processor = BlockProcessor::new(db.clone(), test_metrics(), tagger, None)
+            .with_beacon_adapter(Arc::new(FixedProposer));
+
+        let mut block = test_block();
+        block.header.timestamp = SlotClock::MAINNET.genesis_time + 12 * 100;
+        processor.process_block(&BlockData::from_block(block)).await.unwrap();
+
+        let (slot, index, pubkey, tagged): (Option<i64>, Option<i64>, Option<String>, bool) = sqlx::query_as(
+            "SELECT slot, proposer_index, proposer_pubkey, is_africa_tagged FROM blocks"
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!((slot, index, pubkey.as_deref()), (Some(100), Some(101), Some("0xafrica")));
+        assert!(tagged);
+    }
+}
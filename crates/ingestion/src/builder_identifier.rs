@@ -0,0 +1,308 @@
+//! Builder identification from fee recipients and block extraData.
+
+use anyhow::Result;
+use mev_africa_db::DbPool;
+use regex::{Regex, RegexBuilder};
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Fee recipients that belong to well-known builders.
+const KNOWN_FEE_RECIPIENTS: &[(&str, &str)] = &[
+    ("0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5", "beaverbuild"),
+    ("0x4838b106fce9647bdf1e7877bf73ce8b0bad5f97", "Titan Builder"),
+    ("0x1f9090aae28b8a3dceadf281b0f12828e676c326", "rsync-builder"),
+    ("0xdafea492d9c6733ae3d56b7ed1adb60692c98bc5", "Flashbots"),
+    ("0x690b9a9e9aa1c9db991c7721a92d351db4fac990", "builder0x69"),
+];
+
+/// extraData patterns builders sign their blocks with, matched case-insensitively.
+const EXTRA_DATA_PATTERNS: &[(&str, &str)] = &[
+    (r"beaverbuild", "beaverbuild"),
+    (r"titan", "Titan Builder"),
+    (r"rsync", "rsync-builder"),
+    (r"flashbots|illuminate dmocratize dstribute", "Flashbots"),
+    (r"builder0x69", "builder0x69"),
+    (r"bloxroute", "bloXroute"),
+    (r"buildai", "BuildAI"),
+    (r"jetbldr|jetbuilder", "Jetbuilder"),
+];
+
+/// How much an identification can be trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BuilderConfidence {
+    /// The fee recipient is a known builder but extraData names another one.
+    Low,
+    /// Only extraData matched. extraData is chosen freely by whoever built
+    /// the block, so it can be copied or omitted.
+    Medium,
+    /// The fee recipient is a known builder address.
+    High,
+}
+
+impl BuilderConfidence {
+    /// Value stored in `blocks.builder_confidence` and `builders.confidence`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuilderConfidence::Low => "low",
+            BuilderConfidence::Medium => "medium",
+            BuilderConfidence::High => "high",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "low" => Some(BuilderConfidence::Low),
+            "medium" => Some(BuilderConfidence::Medium),
+            "high" => Some(BuilderConfidence::High),
+            _ => None,
+        }
+    }
+}
+
+/// A builder attributed to a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuilderMatch {
+    /// Canonical builder name.
+    pub name: String,
+    pub confidence: BuilderConfidence,
+}
+
+/// Maps fee recipients and extraData to canonical builder names.
+pub struct BuilderIdentifier {
+    fee_recipients: HashMap<String, String>,
+    extra_data_patterns: Vec<(Regex, String)>,
+}
+
+impl Default for BuilderIdentifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuilderIdentifier {
+    /// Create an identifier with the built-in builder list.
+    pub fn new() -> Self {
+        let fee_recipients = KNOWN_FEE_RECIPIENTS
+            .iter()
+            .map(|(address, name)| (address.to_string(), name.to_string()))
+            .collect();
+        let extra_data_patterns = EXTRA_DATA_PATTERNS
+            .iter()
+            .map(|(pattern, name)| {
+                let regex = extra_data_regex(pattern).expect("built-in extraData patterns are valid");
+                (regex, name.to_string())
+            })
+            .collect();
+
+        Self {
+            fee_recipients,
+            extra_data_patterns,
+        }
+    }
+
+    /// Create an identifier from the builder registry imported with
+    /// `import-builders`, falling back to the built-in list if none was
+    /// imported.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    pub async fn load(db: &DbPool) -> Result<Self> {
+        let registry: Vec<(String, String)> =
+            sqlx::query_as("SELECT name, extra_data_patterns FROM builder_registry ORDER BY id")
+                .fetch_all(db.pool())
+                .await?;
+        if registry.is_empty() {
+            return Ok(Self::new());
+        }
+
+        let fee_recipients: HashMap<String, String> = sqlx::query_as(
+            r#"
+            SELECT b.fee_recipient, r.name
+            FROM builders b
+            JOIN builder_registry r ON b.registry_builder_id = r.id
+            "#,
+        )
+        .fetch_all(db.pool())
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut extra_data_patterns = Vec::new();
+        for (name, patterns) in &registry {
+            for pattern in serde_json::from_str::<Vec<String>>(patterns)? {
+                extra_data_patterns.push((extra_data_regex(&pattern)?, name.clone()));
+            }
+        }
+
+        info!(
+            "Loaded {} registry builders with {} fee recipients",
+            registry.len(),
+            fee_recipients.len()
+        );
+        Ok(Self {
+            fee_recipients,
+            extra_data_patterns,
+        })
+    }
+
+    /// Identify the builder of a block.
+    ///
+    /// # Arguments
+    /// * `fee_recipient` - Block fee recipient address
+    /// * `extra_data_text` - Decoded extraData, see [`decode_extra_data`]
+    ///
+    /// # Returns
+    /// The builder and the confidence of the match, or `None` if neither
+    /// the fee recipient nor the extraData is recognized
+    pub fn identify(&self, fee_recipient: &str, extra_data_text: Option<&str>) -> Option<BuilderMatch> {
+        let by_recipient = self.fee_recipients.get(&fee_recipient.to_lowercase());
+        let by_extra_data = extra_data_text.and_then(|text| {
+            self.extra_data_patterns
+                .iter()
+                .find(|(regex, _)| regex.is_match(text))
+                .map(|(_, name)| name)
+        });
+
+        let (name, confidence) = match (by_recipient, by_extra_data) {
+            (Some(name), Some(signed)) if name != signed => {
+                debug!("Fee recipient {} belongs to {} but extraData names {}", fee_recipient, name, signed);
+                (name, BuilderConfidence::Low)
+            }
+            (Some(name), _) => (name, BuilderConfidence::High),
+            (None, Some(signed)) => (signed, BuilderConfidence::Medium),
+            (None, None) => return None,
+        };
+
+        Some(BuilderMatch {
+            name: name.clone(),
+            confidence,
+        })
+    }
+
+    /// Re-run identification over every stored block, updating the block
+    /// attribution and the `builders` table.
+    ///
+    /// # Returns
+    /// The number of blocks a builder was attributed to
+    pub async fn identify_stored_blocks(&self, db: &DbPool) -> Result<u64> {
+        let blocks: Vec<(i64, String, Option<String>)> =
+            sqlx::query_as("SELECT id, fee_recipient, extra_data_text FROM blocks ORDER BY block_number")
+                .fetch_all(db.pool())
+                .await?;
+
+        let mut tx = db.pool().begin().await?;
+        let mut identified = 0;
+        for (block_id, fee_recipient, extra_data_text) in &blocks {
+            let builder = self.identify(fee_recipient, extra_data_text.as_deref());
+            sqlx::query("UPDATE blocks SET builder_name = ?, builder_confidence = ? WHERE id = ?")
+                .bind(builder.as_ref().map(|b| &b.name))
+                .bind(builder.as_ref().map(|b| b.confidence.as_str()))
+                .bind(block_id)
+                .execute(&mut *tx)
+                .await?;
+            record_builder(&mut tx, fee_recipient, builder.as_ref()).await?;
+            if builder.is_some() {
+                identified += 1;
+            }
+        }
+        tx.commit().await?;
+
+        info!("Identified the builder of {} of {} stored blocks", identified, blocks.len());
+        Ok(identified)
+    }
+}
+
+/// Compile an extraData pattern, matching case-insensitively.
+pub(crate) fn extra_data_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+/// Decode block extraData for display and pattern matching.
+///
+/// Invalid UTF-8 sequences and control characters are dropped, so a
+/// partly binary extraData still yields its readable part.
+pub fn decode_extra_data(extra_data: &[u8]) -> Option<String> {
+    let text: String = String::from_utf8_lossy(extra_data)
+        .chars()
+        .filter(|c| !c.is_control() && *c != char::REPLACEMENT_CHARACTER)
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Insert a fee recipient into `builders`, naming it when an identification
+/// is at least as confident as the stored one.
+pub(crate) async fn record_builder(
+    conn: &mut SqliteConnection,
+    fee_recipient: &str,
+    builder: Option<&BuilderMatch>,
+) -> Result<()> {
+    sqlx::query("INSERT OR IGNORE INTO builders (fee_recipient, is_known) VALUES (?, 0)")
+        .bind(fee_recipient)
+        .execute(&mut *conn)
+        .await?;
+
+    let Some(builder) = builder else {
+        return Ok(());
+    };
+    let stored: Option<String> =
+        sqlx::query_scalar("SELECT confidence FROM builders WHERE fee_recipient = ?")
+            .bind(fee_recipient)
+            .fetch_one(&mut *conn)
+            .await?;
+    if stored
+        .as_deref()
+        .and_then(BuilderConfidence::parse)
+        .is_some_and(|stored| stored > builder.confidence)
+    {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE builders
+        SET builder_name = ?, confidence = ?, is_known = ?, updated_at = datetime('now')
+        WHERE fee_recipient = ?
+        "#,
+    )
+    .bind(&builder.name)
+    .bind(builder.confidence.as_str())
+    .bind(builder.confidence >= BuilderConfidence::Medium)
+    .bind(fee_recipient)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEAVER: &str = "0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5";
+
+    #[test]
+    fn test_identify_by_fee_recipient_and_extra_data() {
+        let identifier = BuilderIdentifier::new();
+
+        let high = identifier.identify(BEAVER, Some("beaverbuild.org")).unwrap();
+        assert_eq!((high.name.as_str(), high.confidence), ("beaverbuild", BuilderConfidence::High));
+
+        let medium = identifier.identify("0x1234", Some("Titan (titanbuilder.xyz)")).unwrap();
+        assert_eq!((medium.name.as_str(), medium.confidence), ("Titan Builder", BuilderConfidence::Medium));
+
+        let low = identifier.identify(BEAVER, Some("Powered by bloXroute")).unwrap();
+        assert_eq!((low.name.as_str(), low.confidence), ("beaverbuild", BuilderConfidence::Low));
+
+        assert_eq!(identifier.identify("0x1234", Some("geth go1.21.5 linux")), None);
+        assert_eq!(identifier.identify("0x1234", None), None);
+    }
+
+    #[test]
+    fn test_decode_extra_data() {
+        assert_eq!(decode_extra_data(b"beaverbuild.org").as_deref(), Some("beaverbuild.org"));
+        assert_eq!(decode_extra_data(b"\x01\xffgeth go1.21\x00").as_deref(), Some("geth go1.21"));
+        assert_eq!(decode_extra_data(b"\x00\x01"), None);
+    }
+}
@@ -0,0 +1,382 @@
+//! Known-builder registry import.
+
+use anyhow::Result;
+use mev_africa_db::DbPool;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use crate::builder_identifier::extra_data_regex;
+
+/// A versioned registry of known builders, read from JSON.
+///
+/// ```json
+/// {
+///   "version": 3,
+///   "builders": [
+///     {
+///       "name": "beaverbuild",
+///       "fee_recipients": ["0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5"],
+///       "pubkeys": [],
+///       "extra_data_patterns": ["beaverbuild"],
+///       "website": "https://beaverbuild.org"
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BuilderRegistry {
+    /// Revision of the registry, stored with every imported builder.
+    pub version: i64,
+    pub builders: Vec<RegistryBuilder>,
+}
+
+/// A builder in the registry.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryBuilder {
+    /// Canonical builder name.
+    pub name: String,
+    #[serde(default)]
+    pub fee_recipients: Vec<String>,
+    /// BLS pubkeys the builder signs relay bids with.
+    #[serde(default)]
+    pub pubkeys: Vec<String>,
+    /// Case-insensitive regexes matched against decoded extraData.
+    #[serde(default)]
+    pub extra_data_patterns: Vec<String>,
+    pub website: Option<String>,
+}
+
+/// Outcome of a registry import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub builders: usize,
+    pub fee_recipients: usize,
+    pub pubkeys: usize,
+    /// Fee recipients or pubkeys listed under more than one builder, and fee
+    /// recipients that were stored under a different name before.
+    pub conflicts: Vec<String>,
+    /// Builders none of whose fee recipients appear in stored blocks.
+    pub unmatched: Vec<String>,
+}
+
+impl BuilderRegistry {
+    /// Read and validate a registry file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_json(&json)
+    }
+
+    /// Parse and validate a registry. Addresses and pubkeys are lowercased.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut registry: Self = serde_json::from_str(json)?;
+
+        let mut names = HashSet::new();
+        for builder in &mut registry.builders {
+            if builder.name.trim().is_empty() {
+                return Err(anyhow::anyhow!("Builder registry entry without a name"));
+            }
+            if !names.insert(builder.name.clone()) {
+                return Err(anyhow::anyhow!("Builder {} is listed more than once", builder.name));
+            }
+            for address in &mut builder.fee_recipients {
+                *address = address.to_lowercase();
+                if !is_hex_of_len(address, 40) {
+                    return Err(anyhow::anyhow!("Invalid fee recipient {} for {}", address, builder.name));
+                }
+            }
+            for pubkey in &mut builder.pubkeys {
+                *pubkey = pubkey.to_lowercase();
+                if !is_hex_of_len(pubkey, 96) {
+                    return Err(anyhow::anyhow!("Invalid pubkey {} for {}", pubkey, builder.name));
+                }
+            }
+            for pattern in &builder.extra_data_patterns {
+                extra_data_regex(pattern).map_err(|e| {
+                    anyhow::anyhow!("Invalid extraData pattern {:?} for {}: {}", pattern, builder.name, e)
+                })?;
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Replace the imported registry with this one.
+    ///
+    /// Fee recipients are upserted into `builders` as known, high confidence
+    /// builders, and fee recipients imported before but no longer listed are
+    /// reset to unknown. A fee recipient or pubkey listed under several builders is
+    /// kept for the first one and reported as a conflict. Everything is
+    /// written in one database transaction.
+    pub async fn import(&self, db: &DbPool) -> Result<ImportReport> {
+        let mut report = ImportReport::default();
+        let mut tx = db.pool().begin().await?;
+
+        let names: Vec<&str> = self.builders.iter().map(|b| b.name.as_str()).collect();
+        let addresses: Vec<&str> = self
+            .builders
+            .iter()
+            .flat_map(|b| b.fee_recipients.iter().map(String::as_str))
+            .collect();
+        // Fee recipients dropped from the registry lose the name it gave them,
+        // so ingestion can attribute them again from scratch
+        sqlx::query(
+            r#"
+            UPDATE builders
+            SET builder_name = NULL, is_known = 0, confidence = NULL, registry_builder_id = NULL,
+                updated_at = datetime('now')
+            WHERE registry_builder_id IS NOT NULL
+              AND fee_recipient NOT IN (SELECT value FROM json_each(?))
+            "#,
+        )
+        .bind(serde_json::to_string(&addresses)?)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE builders SET registry_builder_id = NULL WHERE registry_builder_id IS NOT NULL")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM builder_pubkeys").execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM builder_registry WHERE name NOT IN (SELECT value FROM json_each(?))")
+            .bind(serde_json::to_string(&names)?)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut address_owners: HashMap<&str, &str> = HashMap::new();
+        let mut pubkey_owners: HashMap<&str, &str> = HashMap::new();
+        for builder in &self.builders {
+            let registry_id: i64 = sqlx::query_scalar(
+                r#"
+                INSERT INTO builder_registry (name, website, extra_data_patterns, registry_version)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(name) DO UPDATE SET
+                    website = excluded.website,
+                    extra_data_patterns = excluded.extra_data_patterns,
+                    registry_version = excluded.registry_version,
+                    updated_at = datetime('now')
+                RETURNING id
+                "#,
+            )
+            .bind(&builder.name)
+            .bind(builder.website.as_ref())
+            .bind(serde_json::to_string(&builder.extra_data_patterns)?)
+            .bind(self.version)
+            .fetch_one(&mut *tx)
+            .await?;
+            report.builders += 1;
+
+            let mut matched = false;
+            for address in &builder.fee_recipients {
+                if let Some(owner) = address_owners.get(address.as_str()) {
+                    if *owner != builder.name {
+                        report.conflicts.push(format!(
+                            "Fee recipient {} is listed under both {} and {}, keeping {}",
+                            address, owner, builder.name, owner
+                        ));
+                    }
+                    continue;
+                }
+                address_owners.insert(address, &builder.name);
+
+                let previous: Option<Option<String>> =
+                    sqlx::query_scalar("SELECT builder_name FROM builders WHERE fee_recipient = ?")
+                        .bind(address)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                if let Some(previous) = previous.flatten().filter(|name| *name != builder.name) {
+                    report.conflicts.push(format!(
+                        "Fee recipient {} was named {}, now {}",
+                        address, previous, builder.name
+                    ));
+                }
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO builders (fee_recipient, builder_name, is_known, confidence, registry_builder_id)
+                    VALUES (?, ?, 1, 'high', ?)
+                    ON CONFLICT(fee_recipient) DO UPDATE SET
+                        builder_name = excluded.builder_name,
+                        is_known = 1,
+                        confidence = excluded.confidence,
+                        registry_builder_id = excluded.registry_builder_id,
+                        updated_at = datetime('now')
+                    "#,
+                )
+                .bind(address)
+                .bind(&builder.name)
+                .bind(registry_id)
+                .execute(&mut *tx)
+                .await?;
+                report.fee_recipients += 1;
+
+                matched |= sqlx::query_scalar::<_, bool>(
+                    "SELECT EXISTS(SELECT 1 FROM blocks WHERE fee_recipient = ?)"
+                )
+                .bind(address)
+                .fetch_one(&mut *tx)
+                .await?;
+            }
+            if !matched {
+                report.unmatched.push(builder.name.clone());
+            }
+
+            for pubkey in &builder.pubkeys {
+                if let Some(owner) = pubkey_owners.get(pubkey.as_str()) {
+                    if *owner != builder.name {
+                        report.conflicts.push(format!(
+                            "Pubkey {} is listed under both {} and {}, keeping {}",
+                            pubkey, owner, builder.name, owner
+                        ));
+                    }
+                    continue;
+                }
+                pubkey_owners.insert(pubkey, &builder.name);
+
+                sqlx::query("INSERT INTO builder_pubkeys (builder_registry_id, pubkey) VALUES (?, ?)")
+                    .bind(registry_id)
+                    .bind(pubkey)
+                    .execute(&mut *tx)
+                    .await?;
+                report.pubkeys += 1;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(report)
+    }
+}
+
+/// Whether `value` is `0x` followed by `len` lowercase hex digits.
+fn is_hex_of_len(value: &str, len: usize) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == len && hex.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder_identifier::{record_builder, BuilderConfidence, BuilderIdentifier, BuilderMatch};
+    use crate::test_support::test_db;
+
+    const REGISTRY: &str = r#"{
+        "version": 2,
+        "builders": [
+            {
+                "name": "beaverbuild",
+                "fee_recipients": ["0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5"],
+                "extra_data_patterns": ["beaver"],
+                "website": "https://beaverbuild.org"
+            },
+            {
+                "name": "Copycat",
+                "fee_recipients": [
+                    "0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5",
+                    "0x00000000000000000000000000000000000000cc"
+                ]
+            }
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn test_import_reports_conflicts_and_unmatched_builders() {
+        let db = test_db("builder_registry").await;
+        sqlx::query(
+            r#"
+            INSERT INTO blocks (block_number, block_hash, parent_hash, timestamp, fee_recipient, base_fee, gas_used, total_priority_fees)
+            VALUES (1, '0x01', '0x00', '2024-01-01T00:00:00+00:00', '0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5', '0', 0, '0')
+            "#,
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO builders (fee_recipient, builder_name) VALUES ('0x00000000000000000000000000000000000000cc', 'Old name')")
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let registry = BuilderRegistry::from_json(REGISTRY).unwrap();
+        let report = registry.import(&db).await.unwrap();
+        assert_eq!((report.builders, report.fee_recipients), (2, 2));
+        assert_eq!(report.conflicts.len(), 2);
+        assert!(report.conflicts[0].contains("keeping beaverbuild"));
+        assert!(report.conflicts[1].contains("was named Old name, now Copycat"));
+        assert_eq!(report.unmatched, vec!["Copycat".to_string()]);
+
+        // Importing the same file again gives the same state
+        registry.import(&db).await.unwrap();
+        let builders: Vec<(String, Option<String>, bool)> =
+            sqlx::query_as("SELECT fee_recipient, builder_name, is_known FROM builders ORDER BY fee_recipient")
+                .fetch_all(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(builders.len(), 2);
+        assert_eq!(builders[1].1.as_deref(), Some("beaverbuild"));
+
+        let identifier = BuilderIdentifier::load(&db).await.unwrap();
+        let builder = identifier.identify("0x1234", Some("beaverbuild.org")).unwrap();
+        assert_eq!((builder.name.as_str(), builder.confidence), ("beaverbuild", BuilderConfidence::Medium));
+        // The registry replaces the built-in list
+        assert_eq!(identifier.identify("0x1234", Some("Titan (titanbuilder.xyz)")), None);
+    }
+
+    #[tokio::test]
+    async fn test_reimport_resets_dropped_fee_recipients() {
+        let db = test_db("builder_registry_reimport").await;
+        let dropped = "0x00000000000000000000000000000000000000dd";
+
+        BuilderRegistry::from_json(REGISTRY).unwrap().import(&db).await.unwrap();
+        let next = r#"{
+            "version": 3,
+            "builders": [
+                {"name": "beaverbuild", "fee_recipients": ["0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5"]},
+                {"name": "Dropped", "fee_recipients": ["0x00000000000000000000000000000000000000dd"]}
+            ]
+        }"#;
+        BuilderRegistry::from_json(next).unwrap().import(&db).await.unwrap();
+        BuilderRegistry::from_json(REGISTRY).unwrap().import(&db).await.unwrap();
+
+        let builders: Vec<(String, Option<String>, bool, Option<String>)> = sqlx::query_as(
+            "SELECT fee_recipient, builder_name, is_known, confidence FROM builders ORDER BY fee_recipient",
+        )
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(builders.len(), 3);
+        assert_eq!(builders[0].1.as_deref(), Some("Copycat"));
+        assert_eq!(builders[1], (dropped.to_string(), None, false, None));
+        assert_eq!(builders[2].1.as_deref(), Some("beaverbuild"));
+        let detached: bool = sqlx::query_scalar("SELECT registry_builder_id IS NULL FROM builders WHERE fee_recipient = ?")
+            .bind(dropped)
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert!(detached);
+
+        // Ingestion can name the dropped fee recipient again
+        let mut conn = db.pool().acquire().await.unwrap();
+        let titan = BuilderMatch {
+            name: "Titan Builder".to_string(),
+            confidence: BuilderConfidence::Medium,
+        };
+        record_builder(&mut conn, dropped, Some(&titan)).await.unwrap();
+        let name: Option<String> = sqlx::query_scalar("SELECT builder_name FROM builders WHERE fee_recipient = ?")
+            .bind(dropped)
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("Titan Builder"));
+    }
+
+    #[test]
+    fn test_invalid_registry_entries_are_rejected() {
+        let invalid_address = r#"{"version": 1, "builders": [{"name": "x", "fee_recipients": ["0x12"]}]}"#;
+        assert!(BuilderRegistry::from_json(invalid_address).is_err());
+        let invalid_pattern = r#"{"version": 1, "builders": [{"name": "x", "extra_data_patterns": ["("]}]}"#;
+        assert!(BuilderRegistry::from_json(invalid_pattern).is_err());
+        let duplicate = r#"{"version": 1, "builders": [{"name": "x"}, {"name": "x"}]}"#;
+        assert!(BuilderRegistry::from_json(duplicate).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Persistent resume points for ingestion streams.
+
+use anyhow::Result;
+use mev_africa_db::DbPool;
+use sqlx::Row;
+use crate::finality::FinalityStatus;
+
+/// An ingestion stream with its own checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointStream {
+    /// Head-following ingestion in the given mode.
+    Head(FinalityStatus),
+    /// The current `backfill` run. Cleared once the run completes, so a
+    /// stored checkpoint always belongs to an interrupted run.
+    Backfill,
+}
+
+impl CheckpointStream {
+    /// Value stored in `ingestion_checkpoints.stream`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckpointStream::Head(status) => status.as_str(),
+            CheckpointStream::Backfill => "backfill",
+        }
+    }
+}
+
+/// Reads and writes rows of the `ingestion_checkpoints` table.
+///
+/// A checkpoint is saved after each block is committed. Crashing in between
+/// leaves the checkpoint one block behind, which is harmless because
+/// reprocessing a stored block is idempotent.
+#[derive(Clone)]
+pub struct CheckpointStore {
+    db: DbPool,
+}
+
+impl CheckpointStore {
+    /// Create a new checkpoint store.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+
+    /// Load the last committed block number and hash of a stream.
+    pub async fn load(&self, stream: CheckpointStream) -> Result<Option<(u64, Option<String>)>> {
+        let row = sqlx::query(
+            "SELECT block_number, block_hash FROM ingestion_checkpoints WHERE stream = ?"
+        )
+        .bind(stream.as_str())
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(row.map(|row| (row.get::<i64, _>(0) as u64, row.get(1))))
+    }
+
+    /// Record the last committed block of a stream.
+    ///
+    /// # Arguments
+    /// * `stream` - Stream to update
+    /// * `block_number` - Last committed block number
+    /// * `block_hash` - Hash of that block, if known
+    pub async fn save(
+        &self,
+        stream: CheckpointStream,
+        block_number: u64,
+        block_hash: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ingestion_checkpoints (stream, block_number, block_hash, updated_at)
+            VALUES (?, ?, ?, datetime('now'))
+            ON CONFLICT(stream) DO UPDATE SET
+                block_number = excluded.block_number,
+                block_hash = excluded.block_hash,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(stream.as_str())
+        .bind(block_number as i64)
+        .bind(block_hash)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete the checkpoint of a stream.
+    pub async fn clear(&self, stream: CheckpointStream) -> Result<()> {
+        sqlx::query("DELETE FROM ingestion_checkpoints WHERE stream = ?")
+            .bind(stream.as_str())
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[tokio::test]
+    async fn test_checkpoints_are_per_stream() {
+        let store = CheckpointStore::new(test_db("checkpoints").await);
+        let head = CheckpointStream::Head(FinalityStatus::Latest);
+
+        assert_eq!(store.load(head).await.unwrap(), None);
+
+        store.save(head, 100, Some("0xaa")).await.unwrap();
+        store.save(head, 101, Some("0xbb")).await.unwrap();
+        store.save(CheckpointStream::Backfill, 5, None).await.unwrap();
+
+        assert_eq!(store.load(head).await.unwrap(), Some((101, Some("0xbb".to_string()))));
+        assert_eq!(store.load(CheckpointStream::Backfill).await.unwrap(), Some((5, None)));
+        assert_eq!(
+            store.load(CheckpointStream::Head(FinalityStatus::Finalized)).await.unwrap(),
+            None
+        );
+
+        store.clear(CheckpointStream::Backfill).await.unwrap();
+        assert_eq!(store.load(CheckpointStream::Backfill).await.unwrap(), None);
+        assert_eq!(store.load(head).await.unwrap(), Some((101, Some("0xbb".to_string()))));
+    }
+}
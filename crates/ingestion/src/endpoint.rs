@@ -0,0 +1,221 @@
+//! RPC endpoint selection and health tracking.
+
+use mev_africa_telemetry::Metrics;
+use reqwest::Url;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Consecutive failures after which an endpoint is put in cooldown.
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an unhealthy endpoint is deprioritized before it is tried again.
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+/// A JSON-RPC endpoint. Lower `priority` values are preferred.
+#[derive(Debug, Clone)]
+pub struct RpcEndpoint {
+    pub url: String,
+    pub priority: u32,
+}
+
+impl RpcEndpoint {
+    /// Create a new endpoint.
+    ///
+    /// # Arguments
+    /// * `url` - HTTP/HTTPS JSON-RPC endpoint URL
+    /// * `priority` - Selection priority, lower is preferred
+    pub fn new(url: &str, priority: u32) -> Self {
+        Self {
+            url: url.to_string(),
+            priority,
+        }
+    }
+
+    /// Host (and port) of the endpoint, used in logs and metric labels so
+    /// API keys in the URL path are never exported.
+    pub fn label(&self) -> String {
+        url_label(&self.url)
+    }
+}
+
+/// Host (and port) of a URL, for logging URLs that may carry an API key.
+pub(crate) fn url_label(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(url) => match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => "unknown".to_string(),
+        },
+        Err(_) => "invalid".to_string(),
+    }
+}
+
+#[derive(Default)]
+struct Health {
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+}
+
+impl Health {
+    fn cooling_down(&self, now: Instant) -> bool {
+        self.cooldown_until.is_some_and(|until| until > now)
+    }
+}
+
+struct TrackedEndpoint {
+    endpoint: RpcEndpoint,
+    label: String,
+    health: Mutex<Health>,
+}
+
+/// A prioritized set of endpoints with health tracking.
+///
+/// Endpoints are labelled by host; endpoints sharing a host (e.g. the same
+/// provider with different API keys) get their pool index appended, so each
+/// label names exactly one endpoint.
+///
+/// An endpoint that fails [`FAILURE_THRESHOLD`] times in a row is moved
+/// behind the healthy endpoints for [`COOLDOWN`]. It stays usable as a last
+/// resort, and a single success makes it healthy again.
+pub(crate) struct EndpointPool {
+    endpoints: Vec<TrackedEndpoint>,
+    metrics: Metrics,
+}
+
+impl EndpointPool {
+    pub(crate) fn new(mut endpoints: Vec<RpcEndpoint>, metrics: Metrics) -> Self {
+        endpoints.sort_by_key(|endpoint| endpoint.priority);
+        let hosts: Vec<String> = endpoints.iter().map(RpcEndpoint::label).collect();
+        let endpoints = endpoints
+            .into_iter()
+            .zip(&hosts)
+            .enumerate()
+            .map(|(index, (endpoint, host))| {
+                let label = if hosts.iter().filter(|other| *other == host).count() > 1 {
+                    format!("{}#{}", host, index)
+                } else {
+                    host.clone()
+                };
+                info!("Initialized RPC endpoint {} (priority {})", label, endpoint.priority);
+                metrics.set_rpc_endpoint_healthy(&label, true);
+                TrackedEndpoint {
+                    endpoint,
+                    label,
+                    health: Mutex::new(Health::default()),
+                }
+            })
+            .collect();
+        Self { endpoints, metrics }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub(crate) fn url(&self, index: usize) -> &str {
+        &self.endpoints[index].endpoint.url
+    }
+
+    pub(crate) fn label(&self, index: usize) -> &str {
+        &self.endpoints[index].label
+    }
+
+    /// Endpoint indices in the order they should be tried: healthy endpoints
+    /// by priority, then endpoints in cooldown by priority.
+    pub(crate) fn order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let (mut order, cooling): (Vec<usize>, Vec<usize>) = (0..self.endpoints.len())
+            .partition(|&i| !self.health(i).cooling_down(now));
+        order.extend(cooling);
+        order
+    }
+
+    pub(crate) fn record_success(&self, index: usize) {
+        let mut health = self.health(index);
+        if health.cooldown_until.take().is_some() {
+            info!("RPC endpoint {} recovered", self.label(index));
+            self.metrics.set_rpc_endpoint_healthy(self.label(index), true);
+        }
+        health.consecutive_failures = 0;
+    }
+
+    pub(crate) fn record_failure(&self, index: usize) {
+        let mut health = self.health(index);
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= FAILURE_THRESHOLD {
+            if !health.cooling_down(Instant::now()) {
+                warn!(
+                    "RPC endpoint {} failed {} times in a row, deprioritizing for {}s",
+                    self.label(index),
+                    health.consecutive_failures,
+                    COOLDOWN.as_secs()
+                );
+            }
+            health.cooldown_until = Some(Instant::now() + COOLDOWN);
+            self.metrics.set_rpc_endpoint_healthy(self.label(index), false);
+        }
+    }
+
+    fn health(&self, index: usize) -> std::sync::MutexGuard<'_, Health> {
+        self.endpoints[index]
+            .health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_metrics;
+
+    #[test]
+    fn test_endpoint_label_hides_path() {
+        let endpoint = RpcEndpoint::new("https://ethereum-mainnet.example.com/secret-key", 0);
+        assert_eq!(endpoint.label(), "ethereum-mainnet.example.com");
+        assert_eq!(RpcEndpoint::new("http://127.0.0.1:8545", 0).label(), "127.0.0.1:8545");
+        assert_eq!(url_label("wss://eth-mainnet.example.com/v2/secret-key"), "eth-mainnet.example.com");
+    }
+
+    #[test]
+    fn test_failing_endpoint_is_deprioritized_until_success() {
+        let pool = EndpointPool::new(
+            vec![
+                RpcEndpoint::new("http://backup.test", 1),
+                RpcEndpoint::new("http://primary.test", 0),
+            ],
+            test_metrics(),
+        );
+        assert_eq!(pool.label(pool.order()[0]), "primary.test");
+
+        let primary = pool.order()[0];
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            pool.record_failure(primary);
+        }
+        assert_eq!(pool.order()[0], primary);
+
+        pool.record_failure(primary);
+        assert_eq!(pool.label(pool.order()[0]), "backup.test");
+        assert_eq!(pool.order()[1], primary);
+
+        pool.record_success(primary);
+        assert_eq!(pool.order()[0], primary);
+    }
+
+    #[test]
+    fn test_endpoints_on_the_same_host_get_unique_labels() {
+        let pool = EndpointPool::new(
+            vec![
+                RpcEndpoint::new("https://eth.example.com/key-a", 0),
+                RpcEndpoint::new("https://eth.example.com/key-b", 1),
+                RpcEndpoint::new("https://other.example.com/key-c", 2),
+            ],
+            test_metrics(),
+        );
+        assert_eq!(pool.label(0), "eth.example.com#0");
+        assert_eq!(pool.label(1), "eth.example.com#1");
+        assert_eq!(pool.label(2), "other.example.com");
+    }
+}
@@ -0,0 +1,143 @@
+//! Error types for JSON-RPC calls.
+
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// JSON-RPC error codes that providers use to signal rate limiting.
+const RATE_LIMIT_CODES: &[i64] = &[429, -32005];
+
+/// Error type for JSON-RPC calls.
+#[derive(Debug, thiserror::Error)]
+pub enum RpcError {
+    #[error("Transport error: {0}")]
+    Transport(#[source] reqwest::Error),
+    #[error("Request timed out: {0}")]
+    Timeout(#[source] reqwest::Error),
+    #[error("HTTP status {0}")]
+    HttpStatus(u16),
+    #[error("Rate limited (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("JSON-RPC error {code}: {message}")]
+    JsonRpc { code: i64, message: String },
+    #[error("Invalid response: {0}")]
+    Decode(String),
+    #[error("Daily RPC budget exhausted")]
+    BudgetExhausted,
+}
+
+/// Result type for JSON-RPC calls.
+pub type RpcResult<T> = Result<T, RpcError>;
+
+impl RpcError {
+    /// Label used for the `kind` dimension of `mev_africa_rpc_errors_total`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RpcError::Transport(_) => "transport",
+            RpcError::Timeout(_) => "timeout",
+            RpcError::HttpStatus(_) => "http_status",
+            RpcError::RateLimited { .. } => "rate_limited",
+            RpcError::JsonRpc { .. } => "json_rpc",
+            RpcError::Decode(_) => "decode",
+            RpcError::BudgetExhausted => "budget_exhausted",
+        }
+    }
+
+    /// Whether the endpoint itself misbehaved, so the call should fail over
+    /// to the next endpoint. JSON-RPC errors are answers to the request and
+    /// another node would usually give the same one.
+    pub fn is_endpoint_failure(&self) -> bool {
+        !matches!(self, RpcError::JsonRpc { .. } | RpcError::BudgetExhausted)
+    }
+
+    /// Whether retrying the same call later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RpcError::Transport(_)
+            | RpcError::Timeout(_)
+            | RpcError::RateLimited { .. }
+            | RpcError::Decode(_) => true,
+            RpcError::HttpStatus(status) => *status == 408 || *status >= 500,
+            RpcError::JsonRpc { .. } | RpcError::BudgetExhausted => false,
+        }
+    }
+
+    /// How long the server asked us to wait before retrying, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            RpcError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Classify a transport-level `reqwest` error.
+    pub(crate) fn from_reqwest(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            RpcError::Timeout(error)
+        } else if error.is_decode() {
+            RpcError::Decode(error.to_string())
+        } else {
+            RpcError::Transport(error)
+        }
+    }
+
+    /// Classify a JSON-RPC `error` object.
+    pub(crate) fn from_json_rpc(error: &serde_json::Value) -> Self {
+        let code = error["code"].as_i64().unwrap_or_default();
+        let message = error["message"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string());
+
+        if RATE_LIMIT_CODES.contains(&code) {
+            RpcError::RateLimited { retry_after: None }
+        } else {
+            RpcError::JsonRpc { code, message }
+        }
+    }
+}
+
+/// Parse a `Retry-After` header value, given either as delay seconds or as
+/// an HTTP date.
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - now;
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc2822("Tue, 01 Oct 2024 12:00:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(parse_retry_after("7", now), Some(Duration::from_secs(7)));
+        assert_eq!(
+            parse_retry_after("Tue, 01 Oct 2024 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_retry_after("Tue, 01 Oct 2024 11:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_json_rpc_rate_limit_codes_are_retryable() {
+        let limited = RpcError::from_json_rpc(&json!({"code": -32005, "message": "limit exceeded"}));
+        assert_eq!(limited.kind(), "rate_limited");
+        assert!(limited.is_retryable());
+
+        let not_found = RpcError::from_json_rpc(&json!({"code": -32000, "message": "header not found"}));
+        assert_eq!(not_found.kind(), "json_rpc");
+        assert!(!not_found.is_retryable());
+        assert!(!not_found.is_endpoint_failure());
+    }
+}
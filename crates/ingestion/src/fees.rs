@@ -0,0 +1,74 @@
+//! Priority fee and burn accounting.
+
+use alloy::primitives::U256;
+use alloy::rpc::types::Transaction;
+
+/// Priority fee per gas a transaction actually paid the block producer.
+///
+/// For EIP-1559 transactions this is `min(maxPriorityFeePerGas,
+/// maxFeePerGas - baseFee)`. Legacy and access list transactions pay
+/// `gasPrice - baseFee`. Blocks before London have no base fee, so the
+/// whole gas price is tip.
+///
+/// # Arguments
+/// * `tx` - The transaction
+/// * `base_fee` - Base fee per gas of the block it was included in
+pub fn effective_priority_fee(tx: &Transaction, base_fee: Option<u64>) -> U256 {
+    let base_fee = U256::from(base_fee.unwrap_or(0));
+    match (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+        (Some(max_fee), Some(max_priority_fee)) => {
+            U256::from(max_priority_fee).min(U256::from(max_fee).saturating_sub(base_fee))
+        }
+        _ => U256::from(tx.gas_price.unwrap_or(0)).saturating_sub(base_fee),
+    }
+}
+
+/// Total priority fee a transaction paid, given the gas it used.
+pub fn priority_fee_paid(tx: &Transaction, base_fee: Option<u64>, gas_used: u64) -> U256 {
+    effective_priority_fee(tx, base_fee).saturating_mul(U256::from(gas_used))
+}
+
+/// Base fee burned by a block.
+pub fn burned_fees(base_fee: Option<u64>, gas_used: u64) -> U256 {
+    U256::from(base_fee.unwrap_or(0)).saturating_mul(U256::from(gas_used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn test_effective_priority_fee_by_transaction_type() {
+        let base_fee = Some(30 * GWEI as u64);
+
+        // Tip cap below the headroom: the full cap is paid
+        let capped = Transaction {
+            max_fee_per_gas: Some(100 * GWEI),
+            max_priority_fee_per_gas: Some(2 * GWEI),
+            ..Default::default()
+        };
+        assert_eq!(effective_priority_fee(&capped, base_fee), U256::from(2 * GWEI));
+
+        // Max fee leaves less headroom than the tip cap
+        let squeezed = Transaction {
+            max_fee_per_gas: Some(31 * GWEI),
+            max_priority_fee_per_gas: Some(5 * GWEI),
+            ..Default::default()
+        };
+        assert_eq!(effective_priority_fee(&squeezed, base_fee), U256::from(GWEI));
+
+        // Legacy transactions only have a gas price
+        let legacy = Transaction {
+            gas_price: Some(45 * GWEI),
+            transaction_type: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(effective_priority_fee(&legacy, base_fee), U256::from(15 * GWEI));
+        assert_eq!(priority_fee_paid(&legacy, base_fee, 21_000), U256::from(15 * GWEI * 21_000));
+        assert_eq!(effective_priority_fee(&legacy, None), U256::from(45 * GWEI));
+
+        assert_eq!(burned_fees(base_fee, 10), U256::from(300 * GWEI));
+    }
+}
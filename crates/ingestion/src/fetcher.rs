@@ -0,0 +1,304 @@
+//! Fetching blocks together with the per-block data stored alongside them.
+
+use alloy::rpc::types::Block;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+use crate::error::RpcError;
+use crate::rpc_client::RpcClient;
+use crate::traces::{parse_call_tracer, parse_parity_traces, TransactionTrace};
+
+/// Maximum number of `eth_getTransactionReceipt` calls per batch request.
+const RECEIPT_BATCH_SIZE: usize = 100;
+
+/// Source of blocks for the [`BackfillEngine`](crate::backfill::BackfillEngine).
+#[async_trait]
+pub trait BlockSource: Send + Sync {
+    /// Fetch several blocks with their enrichment data.
+    ///
+    /// Results are returned in the same order as `block_numbers`.
+    async fn fetch_blocks(&self, block_numbers: &[u64]) -> Result<Vec<Result<Option<BlockData>>>>;
+}
+
+/// A block and the data fetched alongside it.
+#[derive(Debug, Clone)]
+pub struct BlockData {
+    /// Block with full transaction objects.
+    pub block: Block,
+    /// Receipts in transaction order, or `None` if they were not fetched.
+    pub receipts: Option<Vec<Value>>,
+    /// Internal calls in transaction order, or `None` if they were not fetched.
+    pub traces: Option<Vec<TransactionTrace>>,
+}
+
+impl BlockData {
+    /// Wrap a block without any enrichment data.
+    pub fn from_block(block: Block) -> Self {
+        Self {
+            block,
+            receipts: None,
+            traces: None,
+        }
+    }
+
+    /// Block number.
+    pub fn number(&self) -> u64 {
+        self.block.header.number
+    }
+
+    /// Block hash as a `0x`-prefixed lowercase hex string.
+    pub fn hash(&self) -> String {
+        format!("{:#x}", self.block.header.hash)
+    }
+
+    /// Transaction hashes in block order, as `0x`-prefixed lowercase hex strings.
+    pub fn transaction_hashes(&self) -> Vec<String> {
+        self.block
+            .transactions
+            .hashes()
+            .map(|hash| format!("{:#x}", hash))
+            .collect()
+    }
+}
+
+/// Fetches blocks and, unless disabled, their transaction receipts and
+/// call traces.
+///
+/// Receipts are requested with `eth_getBlockReceipts`. If the node does not
+/// support it, the fetcher switches to batched `eth_getTransactionReceipt`
+/// calls for the rest of the run. Traces are off by default; when enabled
+/// they are requested with the callTracer of `debug_traceBlockByNumber`,
+/// falling back to `trace_block`, and skipped if the node supports neither.
+/// Once the daily RPC budget is spent, blocks are returned without either.
+pub struct BlockFetcher<'a> {
+    rpc_client: &'a RpcClient,
+    fetch_receipts: bool,
+    fetch_traces: bool,
+    block_receipts_supported: AtomicBool,
+    call_tracer_supported: AtomicBool,
+    parity_traces_supported: AtomicBool,
+    budget_warned: AtomicBool,
+}
+
+impl<'a> BlockFetcher<'a> {
+    /// Create a new block fetcher that also fetches receipts, but not traces.
+    ///
+    /// # Arguments
+    /// * `rpc_client` - RPC client used for all requests
+    pub fn new(rpc_client: &'a RpcClient) -> Self {
+        Self {
+            rpc_client,
+            fetch_receipts: true,
+            fetch_traces: false,
+            block_receipts_supported: AtomicBool::new(true),
+            call_tracer_supported: AtomicBool::new(true),
+            parity_traces_supported: AtomicBool::new(true),
+            budget_warned: AtomicBool::new(false),
+        }
+    }
+
+    /// Enable or disable receipt fetching.
+    pub fn with_receipts(mut self, enabled: bool) -> Self {
+        self.fetch_receipts = enabled;
+        self
+    }
+
+    /// Enable or disable call trace fetching. Requires a node with the
+    /// `debug` or `trace` namespace.
+    pub fn with_traces(mut self, enabled: bool) -> Self {
+        if enabled {
+            info!("Fetching call traces for every block");
+        }
+        self.fetch_traces = enabled;
+        self
+    }
+
+    /// Fetch several blocks with full transactions and their enrichment data.
+    ///
+    /// Results are returned in the same order as `block_numbers`. A block
+    /// whose receipts could not be fetched is an error, so it is retried
+    /// rather than stored incomplete.
+    pub async fn fetch_blocks(&self, block_numbers: &[u64]) -> Result<Vec<Result<Option<BlockData>>>> {
+        let blocks = self.rpc_client.get_blocks(block_numbers).await?;
+
+        Ok(join_all(blocks.into_iter().map(|block| async move {
+            match block? {
+                Some(block) => Ok(Some(self.enrich(BlockData::from_block(block)).await?)),
+                None => Ok(None),
+            }
+        }))
+        .await)
+    }
+
+    async fn enrich(&self, mut data: BlockData) -> Result<BlockData> {
+        if self.fetch_receipts {
+            data.receipts = self
+                .unless_budget_exhausted(self.fetch_receipts(&data).await)
+                .map_err(|e| e.context("Failed to fetch receipts"))?;
+        }
+        if self.fetch_traces {
+            data.traces = self
+                .unless_budget_exhausted(self.fetch_traces(&data).await)
+                .map_err(|e| e.context("Failed to fetch traces"))?
+                .flatten();
+        }
+
+        Ok(data)
+    }
+
+    /// Turn a budget exhausted error into `None`, warning once.
+    fn unless_budget_exhausted<T>(&self, result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if is_budget_exhausted(&e) => {
+                if !self.budget_warned.swap(true, Ordering::Relaxed) {
+                    warn!("Daily RPC budget exhausted, storing blocks without receipts or traces");
+                }
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetch the receipts of a block in transaction order.
+    async fn fetch_receipts(&self, data: &BlockData) -> Result<Vec<Value>> {
+        let block_hash = data.hash();
+        let tx_hashes = data.transaction_hashes();
+        if tx_hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.rpc_client.enrichment_paused() {
+            return Err(RpcError::BudgetExhausted.into());
+        }
+
+        let receipts = match self.fetch_block_receipts(&block_hash).await? {
+            Some(receipts) => receipts,
+            None => self.fetch_receipts_per_transaction(&tx_hashes).await?,
+        };
+
+        if receipts.len() != tx_hashes.len() {
+            return Err(anyhow::anyhow!(
+                "Got {} receipts for {} transactions in block {}",
+                receipts.len(),
+                tx_hashes.len(),
+                block_hash
+            ));
+        }
+        for (receipt, tx_hash) in receipts.iter().zip(&tx_hashes) {
+            let matches = |field: &str, expected: &str| {
+                receipt[field].as_str().is_some_and(|v| v.eq_ignore_ascii_case(expected))
+            };
+            if !matches("transactionHash", tx_hash) || !matches("blockHash", &block_hash) {
+                return Err(anyhow::anyhow!(
+                    "Receipt for {} does not belong to block {}",
+                    tx_hash,
+                    block_hash
+                ));
+            }
+        }
+
+        Ok(receipts)
+    }
+
+    /// Fetch receipts with `eth_getBlockReceipts`. Returns `None` when the
+    /// node does not support the method.
+    async fn fetch_block_receipts(&self, block_hash: &str) -> Result<Option<Vec<Value>>> {
+        if !self.block_receipts_supported.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+
+        match self.rpc_client.get_block_receipts(block_hash).await {
+            Ok(Some(receipts)) => Ok(Some(receipts)),
+            Ok(None) => Err(anyhow::anyhow!("Receipts for block {} not found", block_hash)),
+            Err(e) if is_unsupported_method(&e) => {
+                if self.block_receipts_supported.swap(false, Ordering::Relaxed) {
+                    info!("eth_getBlockReceipts is not supported, fetching receipts per transaction");
+                }
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn fetch_receipts_per_transaction(&self, tx_hashes: &[String]) -> Result<Vec<Value>> {
+        let mut receipts = Vec::with_capacity(tx_hashes.len());
+        for chunk in tx_hashes.chunks(RECEIPT_BATCH_SIZE) {
+            let results = self.rpc_client.get_transaction_receipts(chunk).await?;
+            for (tx_hash, result) in chunk.iter().zip(results) {
+                let receipt = result?
+                    .ok_or_else(|| anyhow::anyhow!("Receipt for {} not found", tx_hash))?;
+                receipts.push(receipt);
+            }
+        }
+        Ok(receipts)
+    }
+
+    /// Fetch the internal calls of a block in transaction order. Returns
+    /// `None` when the node supports neither tracing method.
+    async fn fetch_traces(&self, data: &BlockData) -> Result<Option<Vec<TransactionTrace>>> {
+        let block_number = data.number();
+        let tx_hashes = data.transaction_hashes();
+        if tx_hashes.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+        if self.rpc_client.enrichment_paused() {
+            return Err(RpcError::BudgetExhausted.into());
+        }
+
+        if self.call_tracer_supported.load(Ordering::Relaxed) {
+            match self.rpc_client.get_block_call_traces(block_number).await {
+                Ok(Some(results)) => return parse_call_tracer(&results, &tx_hashes).map(Some),
+                Ok(None) => return Err(anyhow::anyhow!("Traces for block {} not found", block_number)),
+                Err(e) if is_unsupported_method(&e) => {
+                    if self.call_tracer_supported.swap(false, Ordering::Relaxed) {
+                        info!("debug_traceBlockByNumber is not supported, trying trace_block");
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if self.parity_traces_supported.load(Ordering::Relaxed) {
+            match self.rpc_client.get_block_parity_traces(block_number).await {
+                Ok(Some(traces)) => return parse_parity_traces(&traces, &tx_hashes, &data.hash()).map(Some),
+                Ok(None) => return Err(anyhow::anyhow!("Traces for block {} not found", block_number)),
+                Err(e) if is_unsupported_method(&e) => {
+                    if self.parity_traces_supported.swap(false, Ordering::Relaxed) {
+                        warn!("The RPC endpoint supports neither debug_traceBlockByNumber nor trace_block, storing blocks without traces");
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl BlockSource for BlockFetcher<'_> {
+    async fn fetch_blocks(&self, block_numbers: &[u64]) -> Result<Vec<Result<Option<BlockData>>>> {
+        BlockFetcher::fetch_blocks(self, block_numbers).await
+    }
+}
+
+fn is_budget_exhausted(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<RpcError>(), Some(RpcError::BudgetExhausted))
+}
+
+/// Whether an RPC error means the node does not implement the method.
+fn is_unsupported_method(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<RpcError>() {
+        Some(RpcError::JsonRpc { code, message }) => {
+            let message = message.to_lowercase();
+            *code == -32601
+                || message.contains("not supported")
+                || message.contains("does not exist")
+                || message.contains("not available")
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,190 @@
+//! Finality tracking for stored blocks.
+
+use anyhow::Result;
+use mev_africa_db::DbPool;
+use mev_africa_telemetry::Metrics;
+use tracing::{debug, warn};
+use crate::rpc_client::RpcClient;
+
+/// Finality status of a block, matching the JSON-RPC block tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FinalityStatus {
+    /// Unfinalized head of the chain.
+    Latest,
+    /// Justified by the beacon chain; unlikely to be reorged.
+    Safe,
+    /// Finalized by the beacon chain.
+    Finalized,
+}
+
+impl FinalityStatus {
+    /// Value stored in `blocks.finality_status` and used as the RPC block tag.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FinalityStatus::Latest => "latest",
+            FinalityStatus::Safe => "safe",
+            FinalityStatus::Finalized => "finalized",
+        }
+    }
+}
+
+/// Promotes stored blocks to `safe` and `finalized` as finality advances.
+pub struct FinalityTracker {
+    db: DbPool,
+    metrics: Metrics,
+}
+
+impl FinalityTracker {
+    /// Create a new finality tracker.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    /// * `metrics` - Metrics collector
+    pub fn new(db: DbPool, metrics: Metrics) -> Self {
+        Self { db, metrics }
+    }
+
+    /// Query the current safe and finalized heads and promote stored blocks
+    /// at or below them.
+    ///
+    /// Statuses at or above `ingested_as` are skipped, since blocks are
+    /// already stored with at least that status.
+    pub async fn promote(&self, rpc_client: &RpcClient, ingested_as: FinalityStatus) -> Result<()> {
+        if ingested_as < FinalityStatus::Finalized {
+            if let Some((finalized, hash)) = rpc_client
+                .get_tagged_block(FinalityStatus::Finalized.as_str())
+                .await?
+            {
+                self.metrics.set_finalized_block(finalized);
+                self.promote_to(rpc_client, FinalityStatus::Finalized, finalized, &hash).await?;
+            }
+        }
+
+        if ingested_as < FinalityStatus::Safe {
+            if let Some((safe, hash)) = rpc_client
+                .get_tagged_block(FinalityStatus::Safe.as_str())
+                .await?
+            {
+                self.promote_to(rpc_client, FinalityStatus::Safe, safe, &hash).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Promote stored blocks up to `head`, the current `status` head.
+    ///
+    /// Only the highest stored block at or below `head` is compared with the
+    /// canonical chain; if `head` itself is not stored, its canonical hash is
+    /// fetched. Nothing is promoted unless they match, since the stored chain
+    /// may still need a reorg rollback.
+    async fn promote_to(
+        &self,
+        rpc_client: &RpcClient,
+        status: FinalityStatus,
+        head: u64,
+        head_hash: &str,
+    ) -> Result<()> {
+        let stored: Option<(i64, String)> = sqlx::query_as(
+            "SELECT block_number, block_hash FROM blocks WHERE block_number <= ? ORDER BY block_number DESC LIMIT 1"
+        )
+        .bind(head as i64)
+        .fetch_optional(self.db.pool())
+        .await?;
+        let Some((anchor, stored_hash)) = stored else {
+            return Ok(());
+        };
+        let anchor = anchor as u64;
+
+        let canonical_hash = if anchor == head {
+            Some(head_hash.to_string())
+        } else {
+            rpc_client.get_block_hash(anchor).await?
+        };
+        if !canonical_hash.is_some_and(|hash| hash.eq_ignore_ascii_case(&stored_hash)) {
+            warn!(
+                "Stored block {} ({}) is not on the {} chain, skipping promotion",
+                anchor, stored_hash, status.as_str()
+            );
+            return Ok(());
+        }
+
+        let promoted = sqlx::query(
+            r#"
+            UPDATE blocks SET finality_status = ?
+            WHERE block_number <= ?
+              AND finality_status IN ('latest', 'safe')
+              AND finality_status != ?
+            "#,
+        )
+        .bind(status.as_str())
+        .bind(anchor as i64)
+        .bind(status.as_str())
+        .execute(self.db.pool())
+        .await?
+        .rows_affected();
+
+        if promoted > 0 {
+            debug!("Promoted {} blocks to {} (head {}, up to block {})", promoted, status.as_str(), head, anchor);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::RetryPolicy;
+    use crate::test_support::{test_db, test_metrics};
+
+    #[tokio::test]
+    async fn test_promotion_requires_a_canonical_stored_block() {
+        let db = test_db("finality").await;
+        for (number, hash) in [(1, "0x01"), (2, "0x02")] {
+            sqlx::query(
+                r#"
+                INSERT INTO blocks (block_number, block_hash, parent_hash, timestamp, fee_recipient, base_fee, gas_used, total_priority_fees)
+                VALUES (?, ?, '0x00', '2024-01-01T00:00:00+00:00', '0x00', '0', 0, '0')
+                "#,
+            )
+            .bind(number)
+            .bind(hash)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+        let tracker = FinalityTracker::new(db.clone(), test_metrics());
+        let unreachable = RpcClient::new("http://127.0.0.1:1", test_metrics())
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_retries: 0,
+                ..RetryPolicy::default()
+            });
+        let finalized = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM blocks WHERE finality_status = 'finalized'")
+                .fetch_one(db.pool())
+                .await
+                .unwrap()
+        };
+
+        // The head is not stored and the canonical hash of block 2 is unknown
+        assert!(tracker
+            .promote_to(&unreachable, FinalityStatus::Finalized, 5, "0x05")
+            .await
+            .is_err());
+        assert_eq!(finalized().await, 0);
+
+        // The stored head was orphaned
+        tracker
+            .promote_to(&unreachable, FinalityStatus::Finalized, 2, "0xbb")
+            .await
+            .unwrap();
+        assert_eq!(finalized().await, 0);
+
+        tracker
+            .promote_to(&unreachable, FinalityStatus::Finalized, 2, "0x02")
+            .await
+            .unwrap();
+        assert_eq!(finalized().await, 2);
+    }
+}
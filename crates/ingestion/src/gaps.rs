@@ -0,0 +1,117 @@
+//! Gap detection for the blocks table.
+
+use anyhow::Result;
+use mev_africa_db::DbPool;
+use mev_africa_telemetry::Metrics;
+use sqlx::Row;
+use tracing::{info, warn};
+
+/// An inclusive range of missing block numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl BlockRange {
+    /// Number of blocks in the range.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Whether the range is empty. Ranges returned by the scanner never are.
+    pub fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+}
+
+/// Scans `blocks.block_number` for holes between the lowest and highest stored block.
+pub struct GapScanner {
+    db: DbPool,
+    metrics: Metrics,
+}
+
+impl GapScanner {
+    /// Create a new gap scanner.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    /// * `metrics` - Metrics collector
+    pub fn new(db: DbPool, metrics: Metrics) -> Self {
+        Self { db, metrics }
+    }
+
+    /// List missing block ranges and update the `mev_africa_missing_blocks` gauge.
+    pub async fn scan(&self) -> Result<Vec<BlockRange>> {
+        let ranges = find_missing_ranges(&self.db).await?;
+
+        let missing: u64 = ranges.iter().map(BlockRange::len).sum();
+        self.metrics.set_missing_blocks(missing);
+        if missing > 0 {
+            warn!("Found {} missing blocks in {} gaps", missing, ranges.len());
+        } else {
+            info!("No gaps in stored blocks");
+        }
+
+        Ok(ranges)
+    }
+}
+
+async fn find_missing_ranges(db: &DbPool) -> Result<Vec<BlockRange>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT block_number + 1 AS gap_start, next_block_number - 1 AS gap_end
+        FROM (
+            SELECT block_number,
+                   LEAD(block_number) OVER (ORDER BY block_number) AS next_block_number
+            FROM blocks
+        )
+        WHERE next_block_number > block_number + 1
+        ORDER BY block_number
+        "#,
+    )
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| BlockRange {
+            start: row.get::<i64, _>(0) as u64,
+            end: row.get::<i64, _>(1) as u64,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[tokio::test]
+    async fn test_find_missing_ranges() {
+        let db = test_db("gaps").await;
+
+        for number in [10i64, 11, 14, 15, 20] {
+            sqlx::query(
+                r#"
+                INSERT INTO blocks (
+                    block_number, block_hash, parent_hash, timestamp,
+                    fee_recipient, base_fee, gas_used, total_priority_fees
+                ) VALUES (?, ?, '0x', '2024-01-01T00:00:00+00:00', '0xfee', '0', 0, '0')
+                "#,
+            )
+            .bind(number)
+            .bind(format!("0xhash{}", number))
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+
+        let ranges = find_missing_ranges(&db).await.unwrap();
+        assert_eq!(
+            ranges,
+            vec![BlockRange { start: 12, end: 13 }, BlockRange { start: 16, end: 19 }]
+        );
+        assert_eq!(ranges.iter().map(BlockRange::len).sum::<u64>(), 6);
+    }
+}
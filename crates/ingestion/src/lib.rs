@@ -1,9 +1,63 @@
 //! Core ingestion service for MEV Africa data collection.
 
 pub mod rpc_client;
+pub mod endpoint;
+pub mod error;
+pub mod retry;
+pub mod rate_limit;
 pub mod block_processor;
 pub mod validator_tagger;
+pub mod builder_identifier;
+pub mod builder_registry;
+pub mod reorg;
+pub mod finality;
+pub mod new_heads;
+pub mod backfill;
+pub mod gaps;
+pub mod checkpoint;
+pub mod fees;
+pub mod proposer_payment;
+pub mod fetcher;
+pub mod receipts;
+pub mod traces;
 
 pub use block_processor::BlockProcessor;
 pub use rpc_client::RpcClient;
+pub use endpoint::RpcEndpoint;
+pub use error::{RpcError, RpcResult};
+pub use retry::RetryPolicy;
+pub use rate_limit::{RateLimitConfig, RateLimiter};
 pub use validator_tagger::ValidatorTagger;
+pub use reorg::ReorgDetector;
+pub use finality::{FinalityStatus, FinalityTracker};
+pub use new_heads::HeadWatcher;
+pub use backfill::BackfillEngine;
+pub use gaps::GapScanner;
+pub use checkpoint::{CheckpointStore, CheckpointStream};
+pub use fetcher::{BlockData, BlockFetcher, BlockSource};
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use mev_africa_db::DbPool;
+    use mev_africa_telemetry::Metrics;
+    use std::sync::OnceLock;
+
+    /// Shared metrics instance; Prometheus metrics can only be registered once per process.
+    pub fn test_metrics() -> Metrics {
+        static METRICS: OnceLock<Metrics> = OnceLock::new();
+        METRICS.get_or_init(Metrics::default).clone()
+    }
+
+    /// Create a fresh, migrated database in the temp directory.
+    pub async fn test_db(name: &str) -> DbPool {
+        let path = std::env::temp_dir().join(format!(
+            "mev_africa_{}_{}.db",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let db = DbPool::new(path.to_str().unwrap()).await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+}
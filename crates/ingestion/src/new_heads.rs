@@ -0,0 +1,234 @@
+//! New block notifications via WebSocket `eth_subscribe("newHeads")`.
+
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+use crate::rpc_client::RpcClient;
+
+/// How long to wait for the node to confirm the subscription.
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to poll before trying to re-subscribe after a failure.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(60);
+
+/// An active `newHeads` subscription over WebSocket.
+///
+/// Head numbers are forwarded from a background task; the subscription is
+/// considered dropped once [`HeadSubscription::next_head`] returns `None`.
+pub struct HeadSubscription {
+    heads: mpsc::Receiver<u64>,
+    task: JoinHandle<()>,
+}
+
+impl HeadSubscription {
+    /// Connect to a WebSocket endpoint and subscribe to `newHeads`.
+    ///
+    /// # Arguments
+    /// * `ws_url` - WS/WSS JSON-RPC endpoint URL
+    pub async fn connect(ws_url: &str) -> Result<Self> {
+        let (mut ws, _) = connect_async(ws_url).await?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscribe",
+            "params": ["newHeads"],
+            "id": 1
+        });
+        ws.send(Message::Text(request.to_string())).await?;
+
+        let subscription_id = timeout(SUBSCRIBE_TIMEOUT, async {
+            while let Some(message) = ws.next().await {
+                let Message::Text(text) = message? else {
+                    continue;
+                };
+                let response: Value = serde_json::from_str(&text)?;
+                if response["id"] != 1 {
+                    continue;
+                }
+                if let Some(error) = response.get("error") {
+                    return Err(anyhow::anyhow!("eth_subscribe error: {}", error));
+                }
+                return response["result"]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid eth_subscribe response"));
+            }
+            Err(anyhow::anyhow!("WebSocket closed before subscription was confirmed"))
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out waiting for eth_subscribe response"))??;
+
+        debug!("Subscribed to newHeads with id {}", subscription_id);
+
+        let (sender, heads) = mpsc::channel(16);
+        let task = tokio::spawn(async move {
+            while let Some(message) = ws.next().await {
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(Message::Close(_)) | Err(_) => break,
+                    Ok(_) => continue,
+                };
+                let Ok(notification) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                if notification["method"] != "eth_subscription"
+                    || notification["params"]["subscription"] != subscription_id.as_str()
+                {
+                    continue;
+                }
+                let Some(number) = notification["params"]["result"]["number"]
+                    .as_str()
+                    .and_then(|s| u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).ok())
+                else {
+                    continue;
+                };
+                if sender.send(number).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { heads, task })
+    }
+
+    /// Wait for the next head number. Returns `None` once the subscription drops.
+    pub async fn next_head(&mut self) -> Option<u64> {
+        let mut head = self.heads.recv().await?;
+        // Collapse a backlog of heads into the most recent one
+        while let Ok(newer) = self.heads.try_recv() {
+            head = newer;
+        }
+        Some(head)
+    }
+}
+
+impl Drop for HeadSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Waits for new blocks, driven by a `newHeads` subscription when the RPC
+/// client has a WebSocket endpoint, and by fixed-interval polling otherwise.
+///
+/// When the subscription drops the watcher falls back to polling and
+/// periodically tries to re-subscribe.
+pub struct HeadWatcher {
+    poll_interval: Duration,
+    subscription: Option<HeadSubscription>,
+    next_subscribe_attempt: Instant,
+}
+
+impl HeadWatcher {
+    /// Create a new head watcher.
+    ///
+    /// # Arguments
+    /// * `poll_interval` - Polling interval, also used as the maximum wait for a head notification
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            subscription: None,
+            next_subscribe_attempt: Instant::now(),
+        }
+    }
+
+    /// Wait until a new block is likely available.
+    pub async fn wait(&mut self, rpc_client: &RpcClient) {
+        if self.subscription.is_none()
+            && rpc_client.ws_url().is_some()
+            && Instant::now() >= self.next_subscribe_attempt
+        {
+            match rpc_client.subscribe_new_heads().await {
+                Ok(subscription) => {
+                    info!("Subscribed to newHeads, polling only as a fallback");
+                    self.subscription = Some(subscription);
+                }
+                Err(e) => {
+                    warn!("Failed to subscribe to newHeads, polling instead: {}", e);
+                    self.next_subscribe_attempt = Instant::now() + RESUBSCRIBE_DELAY;
+                }
+            }
+        }
+
+        let Some(subscription) = self.subscription.as_mut() else {
+            sleep(self.poll_interval).await;
+            return;
+        };
+
+        match timeout(self.poll_interval, subscription.next_head()).await {
+            Ok(Some(head)) => debug!("New head {}", head),
+            Ok(None) => {
+                warn!("newHeads subscription dropped, falling back to polling");
+                self.subscription = None;
+                self.next_subscribe_attempt = Instant::now() + RESUBSCRIBE_DELAY;
+            }
+            Err(_) => debug!("No head notification within poll interval, polling"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// Serve a single WebSocket connection that confirms the subscription,
+    /// pushes one head per message on `heads` and closes once it is dropped.
+    async fn mock_ws_server(mut heads: mpsc::Receiver<u64>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+
+            let Some(Ok(Message::Text(text))) = ws.next().await else {
+                panic!("expected subscribe request");
+            };
+            let request: Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(request["method"], "eth_subscribe");
+            assert_eq!(request["params"][0], "newHeads");
+
+            let response = json!({"jsonrpc": "2.0", "id": request["id"], "result": "0xsub"});
+            ws.send(Message::Text(response.to_string())).await.unwrap();
+
+            while let Some(head) = heads.recv().await {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "eth_subscription",
+                    "params": {
+                        "subscription": "0xsub",
+                        "result": {"number": format!("0x{:x}", head)}
+                    }
+                });
+                ws.send(Message::Text(notification.to_string())).await.unwrap();
+            }
+
+            ws.close(None).await.unwrap();
+        });
+
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_subscription_forwards_heads_until_closed() {
+        let (push_head, heads) = mpsc::channel(1);
+        let url = mock_ws_server(heads).await;
+        let mut subscription = HeadSubscription::connect(&url).await.unwrap();
+
+        push_head.send(16).await.unwrap();
+        assert_eq!(subscription.next_head().await, Some(16));
+        push_head.send(17).await.unwrap();
+        assert_eq!(subscription.next_head().await, Some(17));
+
+        drop(push_head);
+        assert_eq!(subscription.next_head().await, None);
+    }
+}
@@ -0,0 +1,104 @@
+//! Detection of builder-to-proposer payments in MEV-Boost blocks.
+
+use alloy::primitives::U256;
+use alloy::rpc::types::Block;
+use crate::receipts::ReceiptData;
+
+/// Payment from the block's fee recipient (the builder) to the proposer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposerPayment {
+    /// Address that received the payment, normally the proposer's own fee recipient.
+    pub recipient: String,
+    /// Amount paid in wei.
+    pub amount: U256,
+    pub tx_hash: String,
+}
+
+/// Find the builder's payment to the proposer.
+///
+/// In MEV-Boost blocks the builder sets itself as fee recipient and pays
+/// the proposer with a plain transfer in the last transaction of the block.
+/// Only the last transaction is considered, and it must send a nonzero
+/// value from the fee recipient to a different address. With a receipt, a
+/// reverted transfer is not a payment.
+///
+/// # Arguments
+/// * `block` - Block with full transactions
+/// * `last_receipt` - Receipt of the last transaction, if fetched
+pub fn detect_proposer_payment(block: &Block, last_receipt: Option<&ReceiptData>) -> Option<ProposerPayment> {
+    let tx = block.transactions.as_transactions()?.last()?;
+    let recipient = tx.to?;
+    if tx.from != block.header.miner || recipient == block.header.miner || tx.value.is_zero() {
+        return None;
+    }
+    if last_receipt.is_some_and(|receipt| receipt.status == Some(0)) {
+        return None;
+    }
+
+    Some(ProposerPayment {
+        recipient: format!("{:#x}", recipient),
+        amount: tx.value,
+        tx_hash: format!("{:#x}", tx.hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Address, B256};
+    use alloy::rpc::types::{BlockTransactions, Header, Transaction};
+
+    fn block_with_last_tx(from: Address, to: Option<Address>, value: u64) -> Block {
+        let builder = Address::repeat_byte(0xbb);
+        Block {
+            header: Header {
+                miner: builder,
+                ..Default::default()
+            },
+            transactions: BlockTransactions::Full(vec![
+                Transaction {
+                    hash: B256::repeat_byte(0x01),
+                    from: Address::repeat_byte(0x11),
+                    to: Some(builder),
+                    value: U256::from(5),
+                    ..Default::default()
+                },
+                Transaction {
+                    hash: B256::repeat_byte(0x02),
+                    from,
+                    to,
+                    value: U256::from(value),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_proposer_payment() {
+        let builder = Address::repeat_byte(0xbb);
+        let proposer = Address::repeat_byte(0xaf);
+
+        let payment = detect_proposer_payment(&block_with_last_tx(builder, Some(proposer), 42), None).unwrap();
+        assert_eq!(payment.recipient, format!("{:#x}", proposer));
+        assert_eq!(payment.amount, U256::from(42));
+        assert_eq!(payment.tx_hash, format!("{:#x}", B256::repeat_byte(0x02)));
+
+        // Not from the builder, a self-transfer, zero value, or a contract creation
+        assert_eq!(detect_proposer_payment(&block_with_last_tx(proposer, Some(proposer), 42), None), None);
+        assert_eq!(detect_proposer_payment(&block_with_last_tx(builder, Some(builder), 42), None), None);
+        assert_eq!(detect_proposer_payment(&block_with_last_tx(builder, Some(proposer), 0), None), None);
+        assert_eq!(detect_proposer_payment(&block_with_last_tx(builder, None, 42), None), None);
+
+        let reverted = ReceiptData {
+            status: Some(0),
+            gas_used: 21_000,
+            effective_gas_price: None,
+            contract_address: None,
+            logs: Vec::new(),
+        };
+        let block = block_with_last_tx(builder, Some(proposer), 42);
+        assert_eq!(detect_proposer_payment(&block, Some(&reverted)), None);
+    }
+}
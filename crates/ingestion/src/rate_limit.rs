@@ -0,0 +1,361 @@
+//! Client-side rate limiting and daily request budget.
+
+use chrono::{NaiveDate, Utc};
+use mev_africa_db::DbPool;
+use mev_africa_telemetry::Metrics;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, info, warn};
+
+/// How often daily budget usage is written to the usage store.
+pub const USAGE_SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Weight of methods without an entry in [`DEFAULT_METHOD_WEIGHTS`].
+pub const DEFAULT_METHOD_WEIGHT: u64 = 10;
+
+/// Default compute unit cost per method, modelled on common provider pricing.
+///
+/// `eth_getBlockByNumber/full` is `eth_getBlockByNumber` with full
+/// transaction objects.
+pub const DEFAULT_METHOD_WEIGHTS: &[(&str, u64)] = &[
+    ("eth_blockNumber", 10),
+    ("eth_getBlockByNumber", 16),
+    ("eth_getBlockByNumber/full", 40),
+    ("eth_getTransactionReceipt", 15),
+    ("eth_getBlockReceipts", 250),
+    ("debug_traceBlockByNumber", 500),
+    ("debug_traceBlock", 500),
+    ("trace_block", 500),
+];
+
+/// Rate limiting settings, in compute units.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    /// Sustained rate. `None` disables throttling.
+    pub compute_units_per_second: Option<f64>,
+    /// Bucket size. Defaults to one second worth of compute units.
+    pub burst: Option<f64>,
+    /// Compute units per UTC day. `None` disables the budget.
+    pub daily_budget: Option<u64>,
+    /// Per-method weight overrides, keyed like [`DEFAULT_METHOD_WEIGHTS`].
+    pub method_weights: HashMap<String, u64>,
+}
+
+struct LimiterState {
+    tokens: f64,
+    last_refill: Instant,
+    day: NaiveDate,
+    used_today: u64,
+    /// Usage not written to the usage store yet, by UTC day
+    unsaved: BTreeMap<NaiveDate, u64>,
+    last_saved: Instant,
+}
+
+/// Token bucket over compute units with a daily budget.
+///
+/// Every request is charged the summed weight of its calls. Requests wait
+/// for enough tokens, reserving them up front so concurrent callers are
+/// served in order. Once the daily budget is spent, ingestion keeps going
+/// but [`RateLimiter::budget_exhausted`] tells enrichment to pause until
+/// the next UTC day. Usage is kept in memory unless a usage store is
+/// attached with [`RateLimiter::with_usage_store`], in which case it is
+/// saved every [`USAGE_SAVE_INTERVAL`] and on [`RateLimiter::save_usage`],
+/// and survives restarts.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    weights: HashMap<String, u64>,
+    state: Mutex<LimiterState>,
+    metrics: Metrics,
+    usage_store: Option<DbPool>,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter.
+    ///
+    /// # Arguments
+    /// * `config` - Rate, burst, budget and weight settings
+    /// * `metrics` - Metrics collector
+    pub fn new(config: RateLimitConfig, metrics: Metrics) -> Self {
+        let mut weights: HashMap<String, u64> = DEFAULT_METHOD_WEIGHTS
+            .iter()
+            .map(|(method, weight)| (method.to_string(), *weight))
+            .collect();
+        weights.extend(config.method_weights.clone());
+
+        if let Some(budget) = config.daily_budget {
+            metrics.set_rpc_budget_remaining(budget);
+        }
+
+        let burst = config.burst.or(config.compute_units_per_second).unwrap_or_default();
+        Self {
+            weights,
+            state: Mutex::new(LimiterState {
+                tokens: burst,
+                last_refill: Instant::now(),
+                day: Utc::now().date_naive(),
+                used_today: 0,
+                unsaved: BTreeMap::new(),
+                last_saved: Instant::now(),
+            }),
+            config,
+            metrics,
+            usage_store: None,
+        }
+    }
+
+    /// Record daily budget usage in `rpc_budget_usage`, starting from what
+    /// was already used today. Does nothing without a daily budget.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    pub async fn with_usage_store(mut self, db: DbPool) -> anyhow::Result<Self> {
+        let Some(budget) = self.config.daily_budget else {
+            return Ok(self);
+        };
+
+        let today = Utc::now().date_naive();
+        let used: Option<i64> = sqlx::query_scalar("SELECT compute_units FROM rpc_budget_usage WHERE day = ?")
+            .bind(today.to_string())
+            .fetch_optional(db.pool())
+            .await?;
+        let used = used.unwrap_or_default() as u64;
+        if used > 0 {
+            info!("{} of {} compute units of today's RPC budget already used", used, budget);
+        }
+
+        {
+            let mut state = self.lock();
+            state.day = today;
+            state.used_today = used;
+        }
+        self.metrics.set_rpc_budget_remaining(budget.saturating_sub(used));
+        self.usage_store = Some(db);
+        Ok(self)
+    }
+
+    /// Compute unit cost of a single call.
+    pub fn weight(&self, method: &str, params: &Value) -> u64 {
+        let key = if method == "eth_getBlockByNumber" && params[1] == Value::Bool(true) {
+            "eth_getBlockByNumber/full"
+        } else {
+            method
+        };
+        self.weights.get(key).copied().unwrap_or(DEFAULT_METHOD_WEIGHT)
+    }
+
+    /// Compute unit cost of a JSON-RPC request or batch.
+    pub fn request_weight(&self, payload: &Value) -> u64 {
+        match payload {
+            Value::Array(calls) => calls.iter().map(|call| self.call_weight(call)).sum(),
+            call => self.call_weight(call),
+        }
+    }
+
+    fn call_weight(&self, call: &Value) -> u64 {
+        self.weight(call["method"].as_str().unwrap_or_default(), &call["params"])
+    }
+
+    /// Charge a request against the budget and wait until the bucket allows it.
+    pub async fn acquire(&self, payload: &Value) {
+        let weight = self.request_weight(payload);
+        let wait = self.reserve(weight);
+        let save_due = self.usage_store.is_some() && self.lock().last_saved.elapsed() >= USAGE_SAVE_INTERVAL;
+        if save_due {
+            self.save_usage().await;
+        }
+
+        self.metrics.observe_rpc_throttle_wait(wait.as_secs_f64());
+        if !wait.is_zero() {
+            debug!("Throttling request of {} compute units for {:?}", weight, wait);
+            sleep(wait).await;
+        }
+    }
+
+    /// Whether today's budget is spent, so non-critical requests should pause.
+    pub fn budget_exhausted(&self) -> bool {
+        let Some(budget) = self.config.daily_budget else {
+            return false;
+        };
+        let mut state = self.lock();
+        Self::roll_day(&mut state);
+        state.used_today >= budget
+    }
+
+    /// Add the usage charged since the last save to the usage store. Call on
+    /// shutdown so the last interval is not lost. Failures are logged and the
+    /// usage is kept for the next save, so losing the database never blocks
+    /// RPC requests.
+    pub async fn save_usage(&self) {
+        let Some(db) = &self.usage_store else {
+            return;
+        };
+        let unsaved = {
+            let mut state = self.lock();
+            state.last_saved = Instant::now();
+            std::mem::take(&mut state.unsaved)
+        };
+
+        for (day, compute_units) in unsaved {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO rpc_budget_usage (day, compute_units, updated_at)
+                VALUES (?, ?, datetime('now'))
+                ON CONFLICT(day) DO UPDATE SET
+                    compute_units = compute_units + excluded.compute_units,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(day.to_string())
+            .bind(compute_units as i64)
+            .execute(db.pool())
+            .await;
+            if let Err(e) = result {
+                warn!("Failed to store RPC budget usage: {}", e);
+                *self.lock().unsaved.entry(day).or_default() += compute_units;
+            }
+        }
+    }
+
+    /// Take `weight` tokens and return how long to wait before sending.
+    fn reserve(&self, weight: u64) -> Duration {
+        let mut state = self.lock();
+
+        Self::roll_day(&mut state);
+        state.used_today += weight;
+        if self.usage_store.is_some() {
+            let day = state.day;
+            *state.unsaved.entry(day).or_default() += weight;
+        }
+        if let Some(budget) = self.config.daily_budget {
+            let remaining = budget.saturating_sub(state.used_today);
+            if remaining == 0 && state.used_today - weight < budget {
+                warn!("Daily RPC budget of {} compute units exhausted, pausing enrichment", budget);
+            }
+            self.metrics.set_rpc_budget_remaining(remaining);
+        }
+
+        let Some(rate) = self.config.compute_units_per_second.filter(|rate| *rate > 0.0) else {
+            return Duration::ZERO;
+        };
+        let burst = self.config.burst.unwrap_or(rate);
+        let now = Instant::now();
+        let refill = now.duration_since(state.last_refill).as_secs_f64() * rate;
+        state.tokens = (state.tokens + refill).min(burst);
+        state.last_refill = now;
+
+        // Reserve the tokens even if that leaves the bucket in debt; the
+        // debt is what later callers have to wait for
+        state.tokens -= weight as f64;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+
+    fn roll_day(state: &mut LimiterState) {
+        let today = Utc::now().date_naive();
+        if today != state.day {
+            state.day = today;
+            state.used_today = 0;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_db, test_metrics};
+    use serde_json::json;
+
+    #[test]
+    fn test_full_block_requests_weigh_more() {
+        let limiter = RateLimiter::new(
+            RateLimitConfig {
+                method_weights: HashMap::from([("eth_blockNumber".to_string(), 1)]),
+                ..RateLimitConfig::default()
+            },
+            test_metrics(),
+        );
+
+        let batch = json!([
+            {"method": "eth_getBlockByNumber", "params": ["0x1", true]},
+            {"method": "eth_getBlockByNumber", "params": ["0x1", false]},
+            {"method": "eth_blockNumber", "params": []},
+            {"method": "eth_chainId", "params": []},
+        ]);
+        assert_eq!(limiter.request_weight(&batch), 40 + 16 + 1 + DEFAULT_METHOD_WEIGHT);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_throttles_and_budget_runs_out() {
+        let limiter = RateLimiter::new(
+            RateLimitConfig {
+                compute_units_per_second: Some(10.0),
+                daily_budget: Some(25),
+                ..RateLimitConfig::default()
+            },
+            test_metrics(),
+        );
+        let call = json!({"method": "eth_blockNumber", "params": []});
+
+        // The first call fits in the initial burst, the second has to wait a second
+        assert_eq!(limiter.reserve(limiter.request_weight(&call)), Duration::ZERO);
+        assert_eq!(limiter.reserve(limiter.request_weight(&call)), Duration::from_secs(1));
+        assert!(!limiter.budget_exhausted());
+
+        let start = Instant::now();
+        limiter.acquire(&call).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+        assert!(limiter.budget_exhausted());
+    }
+
+    #[tokio::test]
+    async fn test_budget_usage_survives_restarts() {
+        let db = test_db("rate_limit_usage").await;
+        let config = RateLimitConfig {
+            daily_budget: Some(25),
+            ..RateLimitConfig::default()
+        };
+        let call = json!({"method": "eth_blockNumber", "params": []});
+
+        let limiter = RateLimiter::new(config.clone(), test_metrics())
+            .with_usage_store(db.clone())
+            .await
+            .unwrap();
+        limiter.acquire(&call).await;
+        limiter.acquire(&call).await;
+
+        // Usage is buffered until the save interval passes or it is saved
+        let stored: Option<i64> = sqlx::query_scalar("SELECT compute_units FROM rpc_budget_usage")
+            .fetch_optional(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(stored, None);
+        limiter.save_usage().await;
+        drop(limiter);
+
+        // A restarted limiter picks up the 20 units already used today
+        let limiter = RateLimiter::new(config, test_metrics())
+            .with_usage_store(db.clone())
+            .await
+            .unwrap();
+        assert!(!limiter.budget_exhausted());
+        limiter.acquire(&call).await;
+        assert!(limiter.budget_exhausted());
+        limiter.save_usage().await;
+
+        let stored: i64 = sqlx::query_scalar("SELECT compute_units FROM rpc_budget_usage")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(stored, 30);
+    }
+}
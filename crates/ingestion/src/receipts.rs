@@ -0,0 +1,142 @@
+//! Transaction receipt parsing.
+
+use alloy::primitives::U256;
+use anyhow::Result;
+use mev_africa_db::models::format_wei;
+use serde_json::{json, Value};
+
+/// Receipt fields stored alongside a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptData {
+    /// 1 for success, 0 for reverted. Missing for pre-Byzantium receipts.
+    pub status: Option<i64>,
+    pub gas_used: i64,
+    /// Effective gas price in wei, formatted with [`format_wei`].
+    pub effective_gas_price: Option<String>,
+    pub contract_address: Option<String>,
+    pub logs: Vec<LogData>,
+}
+
+/// An event log emitted by a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogData {
+    pub log_index: i64,
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+impl ReceiptData {
+    /// Parse an `eth_getTransactionReceipt` / `eth_getBlockReceipts` entry.
+    pub fn from_json(receipt: &Value) -> Result<Self> {
+        let status = receipt["status"].as_str().map(parse_quantity).transpose()?;
+        let gas_used = receipt["gasUsed"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Receipt missing gasUsed"))
+            .and_then(parse_quantity)?;
+        let effective_gas_price = receipt["effectiveGasPrice"]
+            .as_str()
+            .map(|s| s.parse::<U256>().map(format_wei))
+            .transpose()?;
+        let contract_address = receipt["contractAddress"].as_str().map(str::to_string);
+
+        let logs = receipt["logs"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Receipt missing logs"))?
+            .iter()
+            .map(LogData::from_json)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            status,
+            gas_used,
+            effective_gas_price,
+            contract_address,
+            logs,
+        })
+    }
+
+    /// JSON summary stored in `transactions.log_summary`: the number of logs
+    /// and the distinct event signatures (`topic0`) in emission order.
+    pub fn log_summary(&self) -> String {
+        let mut signatures: Vec<&str> = Vec::new();
+        for topic0 in self.logs.iter().filter_map(|log| log.topics.first()) {
+            if !signatures.contains(&topic0.as_str()) {
+                signatures.push(topic0);
+            }
+        }
+        json!({ "count": self.logs.len(), "topics": signatures }).to_string()
+    }
+}
+
+impl LogData {
+    fn from_json(log: &Value) -> Result<Self> {
+        let log_index = log["logIndex"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Log missing logIndex"))
+            .and_then(parse_quantity)?;
+        let address = log["address"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Log missing address"))?
+            .to_string();
+        let topics = log["topics"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Log missing topics"))?
+            .iter()
+            .map(|topic| {
+                topic
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid log topic"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = log["data"].as_str().unwrap_or("0x").to_string();
+
+        Ok(Self {
+            log_index,
+            address,
+            topics,
+            data,
+        })
+    }
+}
+
+fn parse_quantity(hex: &str) -> Result<i64> {
+    Ok(i64::from_str_radix(hex.strip_prefix("0x").unwrap_or(hex), 16)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWAP_TOPIC: &str = "0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822";
+    const TRANSFER_TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+    #[test]
+    fn test_parse_receipt_and_summarize_logs() {
+        let receipt = json!({
+            "transactionHash": "0xtx1",
+            "status": "0x1",
+            "gasUsed": "0x1d4c0",
+            "effectiveGasPrice": "0x4a817c800",
+            "contractAddress": null,
+            "logs": [
+                {"logIndex": "0x3", "address": "0xpair", "topics": [TRANSFER_TOPIC, "0xa", "0xb"], "data": "0x01"},
+                {"logIndex": "0x4", "address": "0xpair", "topics": [SWAP_TOPIC, "0xa"], "data": "0x02"},
+                {"logIndex": "0x5", "address": "0xtoken", "topics": [TRANSFER_TOPIC], "data": "0x03"},
+            ]
+        });
+
+        let parsed = ReceiptData::from_json(&receipt).unwrap();
+        assert_eq!(parsed.status, Some(1));
+        assert_eq!(parsed.gas_used, 120_000);
+        assert_eq!(parsed.effective_gas_price.as_deref(), Some("20000000000"));
+        assert_eq!(parsed.contract_address, None);
+        assert_eq!(parsed.logs.len(), 3);
+        assert_eq!(parsed.logs[1].log_index, 4);
+        assert_eq!(parsed.logs[0].topics.len(), 3);
+
+        let summary: Value = serde_json::from_str(&parsed.log_summary()).unwrap();
+        assert_eq!(summary, json!({"count": 3, "topics": [TRANSFER_TOPIC, SWAP_TOPIC]}));
+    }
+}
@@ -0,0 +1,331 @@
+//! Chain reorganization detection and rollback.
+
+use alloy::rpc::types::Block;
+use anyhow::Result;
+use mev_africa_db::DbPool;
+use mev_africa_telemetry::Metrics;
+use tracing::{info, warn};
+use crate::rpc_client::RpcClient;
+
+/// Default number of blocks to walk back when searching for a common ancestor.
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 64;
+
+/// A chain reorganization that was detected and rolled back.
+#[derive(Debug, Clone)]
+pub struct ReorgEvent {
+    /// Highest block number shared by the stored and the canonical chain.
+    pub common_ancestor: u64,
+    /// Stored hash of the common ancestor, if it is stored.
+    pub common_ancestor_hash: Option<String>,
+    /// Number of stored blocks above the common ancestor that were orphaned.
+    pub depth: u64,
+    /// Block number of the orphaned head that was checked against the new chain.
+    pub old_head_number: u64,
+    /// Stored (orphaned) hash at `old_head_number`.
+    pub old_head_hash: String,
+    /// Canonical hash at `old_head_number`.
+    pub new_head_hash: String,
+    /// Number of block rows deleted by the rollback.
+    pub orphaned_blocks: u64,
+}
+
+/// Detects reorgs by comparing parent hashes against stored blocks and
+/// rolls the database back to the common ancestor.
+pub struct ReorgDetector {
+    db: DbPool,
+    metrics: Metrics,
+    max_depth: u64,
+}
+
+impl ReorgDetector {
+    /// Create a new reorg detector.
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    /// * `metrics` - Metrics collector
+    /// * `max_depth` - Maximum number of blocks to walk back before giving up
+    pub fn new(db: DbPool, metrics: Metrics, max_depth: u64) -> Self {
+        Self {
+            db,
+            metrics,
+            max_depth,
+        }
+    }
+
+    /// Check whether a freshly fetched block extends the stored chain.
+    ///
+    /// If the block's `parentHash` differs from the stored hash of
+    /// `block_number - 1`, walks back to the common ancestor, deletes the
+    /// orphaned blocks together with their transactions and annotations,
+    /// and records the reorg. The caller should resume ingestion from
+    /// `common_ancestor + 1`.
+    ///
+    /// # Arguments
+    /// * `rpc_client` - RPC client used to fetch canonical hashes
+    /// * `block` - The block fetched from RPC
+    pub async fn check_and_rollback(
+        &self,
+        rpc_client: &RpcClient,
+        block: &Block,
+    ) -> Result<Option<ReorgEvent>> {
+        let block_number = block.header.number;
+        let parent_hash = format!("{:#x}", block.header.parent_hash);
+
+        if block_number == 0 {
+            return Ok(None);
+        }
+
+        let old_head_number = block_number - 1;
+        let old_head_hash = match self.stored_hash(old_head_number).await? {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        if old_head_hash.eq_ignore_ascii_case(&parent_hash) {
+            return Ok(None);
+        }
+
+        warn!(
+            "Reorg detected at block {}: stored parent {} but canonical parent is {}",
+            block_number, old_head_hash, parent_hash
+        );
+
+        let (common_ancestor, ancestor_hash) = self
+            .find_common_ancestor(rpc_client, old_head_number)
+            .await?;
+        let mut event = ReorgEvent {
+            common_ancestor,
+            common_ancestor_hash: ancestor_hash,
+            depth: old_head_number - common_ancestor,
+            old_head_number,
+            old_head_hash,
+            new_head_hash: parent_hash,
+            orphaned_blocks: 0,
+        };
+        event.orphaned_blocks = rollback_to(&self.db, &event).await?;
+        self.metrics.inc_reorgs();
+
+        info!(
+            "Rolled back {} orphaned blocks to common ancestor {} (depth {})",
+            event.orphaned_blocks, event.common_ancestor, event.depth
+        );
+
+        Ok(Some(event))
+    }
+
+    /// Walk back from a diverging block until the stored and canonical hashes agree.
+    async fn find_common_ancestor(
+        &self,
+        rpc_client: &RpcClient,
+        diverged_at: u64,
+    ) -> Result<(u64, Option<String>)> {
+        let mut block_number = diverged_at;
+        while block_number > 0 {
+            block_number -= 1;
+            if diverged_at - block_number > self.max_depth {
+                return Err(anyhow::anyhow!(
+                    "Reorg at block {} is deeper than the maximum depth of {}",
+                    diverged_at,
+                    self.max_depth
+                ));
+            }
+
+            let stored = match self.stored_hash(block_number).await? {
+                Some(hash) => hash,
+                // Nothing stored below this point, so nothing older can be orphaned
+                None => return Ok((block_number, None)),
+            };
+            let canonical = rpc_client
+                .get_block_hash(block_number)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Canonical block {} not found", block_number))?;
+
+            if stored.eq_ignore_ascii_case(&canonical) {
+                return Ok((block_number, Some(stored)));
+            }
+        }
+
+        Ok((0, self.stored_hash(0).await?))
+    }
+
+    async fn stored_hash(&self, block_number: u64) -> Result<Option<String>> {
+        let hash: Option<String> = sqlx::query_scalar(
+            "SELECT block_hash FROM blocks WHERE block_number = ?"
+        )
+        .bind(block_number as i64)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(hash)
+    }
+}
+
+/// Delete every block above the common ancestor (with its transactions, logs,
+/// call traces, withdrawals and annotations) and record the reorg, all in one database transaction.
+///
+/// Returns the number of orphaned block rows deleted.
+async fn rollback_to(db: &DbPool, event: &ReorgEvent) -> Result<u64> {
+    let ancestor = event.common_ancestor as i64;
+    let mut tx = db.pool().begin().await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM annotations
+        WHERE block_id IN (SELECT id FROM blocks WHERE block_number > ?)
+           OR transaction_id IN (
+               SELECT t.id FROM transactions t
+               JOIN blocks b ON t.block_id = b.id
+               WHERE b.block_number > ?
+           )
+        "#,
+    )
+    .bind(ancestor)
+    .bind(ancestor)
+    .execute(&mut *tx)
+    .await?;
+
+    for table in ["logs", "internal_calls", "internal_transfers", "failed_subcalls"] {
+        sqlx::query(&format!(
+            r#"
+            DELETE FROM {} WHERE transaction_id IN (
+                SELECT t.id FROM transactions t
+                JOIN blocks b ON t.block_id = b.id
+                WHERE b.block_number > ?
+            )
+            "#,
+            table
+        ))
+        .bind(ancestor)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "DELETE FROM withdrawals WHERE block_id IN (SELECT id FROM blocks WHERE block_number > ?)"
+    )
+    .bind(ancestor)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM transactions WHERE block_id IN (SELECT id FROM blocks WHERE block_number > ?)"
+    )
+    .bind(ancestor)
+    .execute(&mut *tx)
+    .await?;
+
+    let orphaned = sqlx::query("DELETE FROM blocks WHERE block_number > ?")
+        .bind(ancestor)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    sqlx::query(
+        r#"
+        INSERT INTO reorgs (
+            common_ancestor_number, common_ancestor_hash, depth,
+            old_head_number, old_head_hash, new_head_hash, orphaned_block_count
+        ) VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(ancestor)
+    .bind(&event.common_ancestor_hash)
+    .bind(event.depth as i64)
+    .bind(event.old_head_number as i64)
+    .bind(&event.old_head_hash)
+    .bind(&event.new_head_hash)
+    .bind(orphaned as i64)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(orphaned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+    use sqlx::Row;
+
+    async fn insert_block(db: &DbPool, number: i64) -> i64 {
+        sqlx::query(
+            r#"
+            INSERT INTO blocks (
+                block_number, block_hash, parent_hash, timestamp,
+                fee_recipient, base_fee, gas_used, total_priority_fees
+            ) VALUES (?, ?, ?, '2024-01-01T00:00:00+00:00', '0xfee', '0', 0, '0')
+            RETURNING id
+            "#,
+        )
+        .bind(number)
+        .bind(format!("0xhash{}", number))
+        .bind(format!("0xhash{}", number - 1))
+        .fetch_one(db.pool())
+        .await
+        .unwrap()
+        .get(0)
+    }
+
+    #[tokio::test]
+    async fn test_rollback_removes_orphaned_rows() {
+        let db = test_db("reorg_rollback").await;
+        for number in 1..=5 {
+            let block_id = insert_block(&db, number).await;
+            let tx_id: i64 = sqlx::query(
+                r#"
+                INSERT INTO transactions (
+                    block_id, tx_hash, position_index, sender_address, max_priority_fee
+                ) VALUES (?, ?, 0, '0xsender', '0')
+                RETURNING id
+                "#,
+            )
+            .bind(block_id)
+            .bind(format!("0xtx{}", number))
+            .fetch_one(db.pool())
+            .await
+            .unwrap()
+            .get(0);
+            sqlx::query("INSERT INTO annotations (transaction_id, tag) VALUES (?, 'test')")
+                .bind(tx_id)
+                .execute(db.pool())
+                .await
+                .unwrap();
+        }
+
+        let event = ReorgEvent {
+            common_ancestor: 3,
+            common_ancestor_hash: Some("0xhash3".to_string()),
+            depth: 2,
+            old_head_number: 5,
+            old_head_hash: "0xhash5".to_string(),
+            new_head_hash: "0xother5".to_string(),
+            orphaned_blocks: 0,
+        };
+        let orphaned = rollback_to(&db, &event).await.unwrap();
+        assert_eq!(orphaned, 2);
+
+        let max_block: i64 = sqlx::query_scalar("SELECT MAX(block_number) FROM blocks")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(max_block, 3);
+
+        let tx_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(tx_count, 3);
+
+        let annotation_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM annotations")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(annotation_count, 3);
+
+        let depth: i64 = sqlx::query_scalar("SELECT depth FROM reorgs")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(depth, 2);
+    }
+}
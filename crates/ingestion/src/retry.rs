@@ -0,0 +1,88 @@
+//! Retry policy for JSON-RPC calls.
+
+use std::time::Duration;
+
+/// Default number of retries after the first attempt.
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Default delay before the first retry.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Default upper bound for the backoff delay.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Exponential backoff with jitter.
+///
+/// The delay before retry `n` is drawn from `[cap / 2, cap]`, where
+/// `cap = min(base_delay * 2^n, max_delay)`. A `Retry-After` hint from the
+/// server takes precedence, but is also capped at `max_delay` so a large
+/// value cannot stall ingestion.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (starting at 0).
+    ///
+    /// # Arguments
+    /// * `retry` - Number of retries already made
+    /// * `retry_after` - Delay requested by the server, if any
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+
+        let cap = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        cap / 2 + (cap / 2).mul_f64(rand::random::<f64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_within_bounds() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+
+        for _ in 0..100 {
+            let first = policy.delay(0, None);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+
+            let third = policy.delay(2, None);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+
+            let capped = policy.delay(9, None);
+            assert!(capped >= Duration::from_millis(500) && capped <= Duration::from_secs(1));
+        }
+
+        assert_eq!(policy.delay(0, Some(Duration::from_millis(700))), Duration::from_millis(700));
+    }
+
+    #[test]
+    fn test_retry_after_is_capped() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(86_400))), DEFAULT_MAX_DELAY);
+        assert_eq!(policy.delay(0, Some(Duration::MAX)), DEFAULT_MAX_DELAY);
+    }
+}
@@ -1,69 +1,551 @@
 //! Ethereum RPC client for block ingestion.
 
+use alloy::rpc::types::{Block, Transaction};
 use anyhow::Result;
-use reqwest::Client;
+use chrono::Utc;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, StatusCode};
 use serde_json::{json, Value};
-use tokio::time::Instant;
-use tracing::{debug, info};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, info, warn};
+use mev_africa_db::DbPool;
 use mev_africa_telemetry::Metrics;
+use crate::endpoint::{url_label, EndpointPool, RpcEndpoint};
+use crate::error::{parse_retry_after, RpcError, RpcResult};
+use crate::new_heads::HeadSubscription;
+use crate::rate_limit::{RateLimitConfig, RateLimiter};
+use crate::retry::RetryPolicy;
+
+/// Default timeout for a single HTTP request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Ethereum RPC client wrapper.
+///
+/// Requests go to the highest-priority healthy endpoint and fail over to
+/// the next one when an endpoint cannot be reached or answers with an HTTP
+/// error. When every endpoint failed with a retryable error, the call is
+/// retried according to the [`RetryPolicy`]. JSON-RPC error responses are
+/// returned as-is, since another node would usually answer the same way.
 pub struct RpcClient {
     client: Client,
-    rpc_url: String,
+    endpoints: EndpointPool,
+    quorum: usize,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    ws_url: Option<String>,
     metrics: Metrics,
+    next_id: AtomicU64,
 }
 
 impl RpcClient {
-    /// Create a new RPC client.
+    /// Create a new RPC client for a single endpoint.
     ///
     /// # Arguments
     /// * `rpc_url` - HTTP/HTTPS JSON-RPC endpoint URL (e.g., Chainstack endpoint)
     /// * `metrics` - Metrics collector
     pub fn new(rpc_url: &str, metrics: Metrics) -> Result<Self> {
-        info!("Initialized RPC client for {}", rpc_url);
+        Self::from_endpoints(vec![RpcEndpoint::new(rpc_url, 0)], metrics)
+    }
 
+    /// Create a new RPC client that fails over between several endpoints.
+    ///
+    /// # Arguments
+    /// * `endpoints` - JSON-RPC endpoints; lower priorities are tried first
+    /// * `metrics` - Metrics collector
+    pub fn from_endpoints(endpoints: Vec<RpcEndpoint>, metrics: Metrics) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(anyhow::anyhow!("At least one RPC endpoint is required"));
+        }
         Ok(Self {
-            client: Client::new(),
-            rpc_url: rpc_url.to_string(),
+            client: Client::builder().timeout(DEFAULT_REQUEST_TIMEOUT).build()?,
+            endpoints: EndpointPool::new(endpoints, metrics.clone()),
+            quorum: 1,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            ws_url: None,
             metrics,
+            next_id: AtomicU64::new(1),
         })
     }
 
-    async fn call_rpc(&self, method: &str, params: Value) -> Result<Value> {
+    /// Require block hashes to agree across `quorum` endpoints before a
+    /// fetched block is returned. A quorum of 1 disables the check.
+    ///
+    /// # Arguments
+    /// * `quorum` - Number of endpoints that must report the same hash
+    pub fn with_quorum(mut self, quorum: usize) -> Result<Self> {
+        if quorum == 0 || quorum > self.endpoints.len() {
+            return Err(anyhow::anyhow!(
+                "RPC quorum must be between 1 and the number of endpoints ({}), got {}",
+                self.endpoints.len(),
+                quorum
+            ));
+        }
+        if quorum > 1 {
+            info!("Requiring {} endpoints to agree on block hashes", quorum);
+        }
+        self.quorum = quorum;
+        Ok(self)
+    }
+
+    /// Set the retry policy for failed calls.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Throttle requests client-side and track a daily compute unit budget.
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        if let Some(rate) = config.compute_units_per_second {
+            info!("Limiting RPC requests to {} compute units per second", rate);
+        }
+        if let Some(budget) = config.daily_budget {
+            info!("Daily RPC budget: {} compute units", budget);
+        }
+        self.rate_limiter = Some(RateLimiter::new(config, self.metrics.clone()));
+        self
+    }
+
+    /// Keep daily budget usage in the database, so restarting does not reset
+    /// the budget. Call after [`RpcClient::with_rate_limit`].
+    ///
+    /// # Arguments
+    /// * `db` - Database pool
+    pub async fn with_budget_store(mut self, db: DbPool) -> Result<Self> {
+        if let Some(rate_limiter) = self.rate_limiter.take() {
+            self.rate_limiter = Some(rate_limiter.with_usage_store(db).await?);
+        }
+        Ok(self)
+    }
+
+    /// Write daily budget usage that has not been saved yet to the budget
+    /// store. Call before exiting so the last few seconds of usage are kept.
+    pub async fn save_budget_usage(&self) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.save_usage().await;
+        }
+    }
+
+    /// Whether the daily budget is spent. Non-critical enrichment requests
+    /// are refused with [`RpcError::BudgetExhausted`] until the next UTC day.
+    pub fn enrichment_paused(&self) -> bool {
+        self.rate_limiter
+            .as_ref()
+            .is_some_and(RateLimiter::budget_exhausted)
+    }
+
+    /// Set the timeout for a single HTTP request.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.client = Client::builder().timeout(timeout).build()?;
+        Ok(self)
+    }
+
+    /// Set a WebSocket endpoint used for `newHeads` subscriptions.
+    ///
+    /// # Arguments
+    /// * `ws_url` - WS/WSS JSON-RPC endpoint URL
+    pub fn with_ws_url(mut self, ws_url: &str) -> Self {
+        info!("Using WebSocket endpoint {} for new head notifications", url_label(ws_url));
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// The WebSocket endpoint, if configured.
+    pub fn ws_url(&self) -> Option<&str> {
+        self.ws_url.as_deref()
+    }
+
+    /// Subscribe to `newHeads` over the configured WebSocket endpoint.
+    pub async fn subscribe_new_heads(&self) -> Result<HeadSubscription> {
+        let ws_url = self
+            .ws_url
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No WebSocket endpoint configured"))?;
+        HeadSubscription::connect(ws_url).await
+    }
+
+    /// Run `attempt` against the endpoints until one of them responds,
+    /// retrying with backoff while the failures are retryable.
+    ///
+    /// Returns the index of the endpoint that responded along with its response.
+    async fn execute<T, F, Fut>(&self, operation: &str, mut attempt: F) -> RpcResult<(usize, T)>
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = RpcResult<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            let error = match self.with_failover(operation, &mut attempt).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+            if !error.is_retryable() || retry >= self.retry_policy.max_retries {
+                return Err(error);
+            }
+
+            let delay = self.retry_policy.delay(retry, error.retry_after());
+            retry += 1;
+            warn!(
+                "{} failed: {}. Retrying in {:.1}s ({}/{})",
+                operation,
+                error,
+                delay.as_secs_f64(),
+                retry,
+                self.retry_policy.max_retries
+            );
+            sleep(delay).await;
+        }
+    }
+
+    /// Run `attempt` against each endpoint in order until one of them
+    /// responds or fails with a non-endpoint error.
+    async fn with_failover<T, F, Fut>(&self, operation: &str, attempt: &mut F) -> RpcResult<(usize, T)>
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = RpcResult<T>>,
+    {
+        let order = self.endpoints.order();
+        let mut last_error = None;
+        for (position, &index) in order.iter().enumerate() {
+            match attempt(index).await {
+                Ok(response) => {
+                    self.endpoints.record_success(index);
+                    return Ok((index, response));
+                }
+                Err(e) => {
+                    self.metrics.inc_rpc_errors(e.kind());
+                    if !e.is_endpoint_failure() {
+                        self.endpoints.record_success(index);
+                        return Err(e);
+                    }
+                    self.endpoints.record_failure(index);
+                    if position + 1 < order.len() {
+                        warn!(
+                            "{} failed on {}, trying next endpoint: {}",
+                            operation,
+                            self.endpoints.label(index),
+                            e
+                        );
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| RpcError::Decode("No RPC endpoints configured".to_string())))
+    }
+
+    async fn call_rpc(&self, operation: &str, method: &str, params: Value) -> RpcResult<(usize, Value)> {
+        self.execute(operation, |index| self.call_rpc_on(index, operation, method, &params))
+            .await
+    }
+
+    /// Send a single call to one endpoint.
+    async fn call_rpc_on(
+        &self,
+        index: usize,
+        operation: &str,
+        method: &str,
+        params: &Value,
+    ) -> RpcResult<Value> {
         let payload = json!({
             "jsonrpc": "2.0",
             "method": method,
             "params": params,
-            "id": 1
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed)
         });
 
+        let start = Instant::now();
+        let response = self.post(index, &payload).await;
+        self.metrics.observe_rpc_latency(
+            operation,
+            self.endpoints.label(index),
+            start.elapsed().as_secs_f64(),
+        );
+        let mut result = response?;
+
+        // Check for RPC error
+        if let Some(error) = result.get("error") {
+            return Err(RpcError::from_json_rpc(error));
+        }
+
+        Ok(result["result"].take())
+    }
+
+    /// Send several calls as a single JSON-RPC batch request.
+    ///
+    /// The outer `Result` fails if the batch as a whole could not be sent or
+    /// decoded. Each inner `Result` holds the outcome of the call at the same
+    /// position in `calls`, so one failing call does not fail the others.
+    /// Entries that failed with a retryable error (typically a per-entry rate
+    /// limit) are re-requested with backoff.
+    ///
+    /// Also returns the index of the endpoint that served each entry.
+    async fn call_batch(
+        &self,
+        operation: &str,
+        calls: &[(&str, Value)],
+    ) -> RpcResult<(Vec<usize>, Vec<RpcResult<Value>>)> {
+        let (index, mut results) = self
+            .execute(operation, |index| self.call_batch_on(index, operation, calls))
+            .await?;
+        let mut served_by = vec![index; results.len()];
+
+        let mut retry = 0;
+        while retry < self.retry_policy.max_retries {
+            let positions: Vec<usize> = results
+                .iter()
+                .enumerate()
+                .filter(|(_, result)| result.as_ref().is_err_and(RpcError::is_retryable))
+                .map(|(position, _)| position)
+                .collect();
+            if positions.is_empty() {
+                break;
+            }
+
+            let retry_after = positions
+                .iter()
+                .filter_map(|&position| results[position].as_ref().err()?.retry_after())
+                .max();
+            let delay = self.retry_policy.delay(retry, retry_after);
+            retry += 1;
+            debug!(
+                "Retrying {} failed entries of {} in {:.1}s ({}/{})",
+                positions.len(),
+                operation,
+                delay.as_secs_f64(),
+                retry,
+                self.retry_policy.max_retries
+            );
+            sleep(delay).await;
+
+            let retry_calls: Vec<(&str, Value)> =
+                positions.iter().map(|&position| calls[position].clone()).collect();
+            match self
+                .execute(operation, |index| self.call_batch_on(index, operation, &retry_calls))
+                .await
+            {
+                Ok((index, retried)) => {
+                    for (position, result) in positions.into_iter().zip(retried) {
+                        results[position] = result;
+                        served_by[position] = index;
+                    }
+                }
+                // Keep the original per-entry errors
+                Err(e) => {
+                    warn!("Retrying failed entries of {} failed: {}", operation, e);
+                    break;
+                }
+            }
+        }
+
+        Ok((served_by, results))
+    }
+
+    /// Send a batch request to one endpoint.
+    async fn call_batch_on(
+        &self,
+        index: usize,
+        operation: &str,
+        calls: &[(&str, Value)],
+    ) -> RpcResult<Vec<RpcResult<Value>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let first_id = self.next_id.fetch_add(calls.len() as u64, Ordering::Relaxed);
+        let ids: Vec<u64> = (first_id..first_id + calls.len() as u64).collect();
+        let payload: Vec<Value> = calls
+            .iter()
+            .zip(&ids)
+            .map(|((method, params), id)| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": method,
+                    "params": params,
+                    "id": id
+                })
+            })
+            .collect();
+
+        let start = Instant::now();
+        let response = self.post(index, &Value::Array(payload)).await;
+        self.metrics.observe_rpc_latency(
+            operation,
+            self.endpoints.label(index),
+            start.elapsed().as_secs_f64(),
+        );
+
+        match response? {
+            Value::Array(responses) => {
+                let results = match_batch_responses(&ids, responses);
+                for error in results.iter().filter_map(|result| result.as_ref().err()) {
+                    self.metrics.inc_rpc_errors(error.kind());
+                }
+                Ok(results)
+            }
+            // Some nodes answer an unsupported batch with a single error object
+            other => match other.get("error") {
+                Some(error) => Err(RpcError::from_json_rpc(error)),
+                None => Err(RpcError::Decode(format!("Unexpected batch response: {}", other))),
+            },
+        }
+    }
+
+    /// POST a JSON-RPC payload to an endpoint and decode the JSON response.
+    async fn post(&self, index: usize, payload: &Value) -> RpcResult<Value> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(payload).await;
+        }
+
         let response = self.client
-            .post(&self.rpc_url)
-            .json(&payload)
+            .post(self.endpoints.url(index))
+            .json(payload)
             .send()
-            .await?;
+            .await
+            .map_err(RpcError::from_reqwest)?;
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("RPC request failed with status: {}", response.status()));
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, Utc::now()));
+            return Err(RpcError::RateLimited { retry_after });
+        }
+        if !status.is_success() {
+            return Err(RpcError::HttpStatus(status.as_u16()));
         }
 
-        let result: Value = response.json().await?;
-        
-        // Check for RPC error
-        if let Some(error) = result.get("error") {
-            return Err(anyhow::anyhow!("RPC error: {}", error));
+        response.json().await.map_err(RpcError::from_reqwest)
+    }
+
+    /// Check fetched blocks against the other endpoints until each block hash
+    /// is confirmed by `quorum` endpoints in total.
+    ///
+    /// Blocks that do not reach the quorum are replaced with an error, so
+    /// callers retry them instead of storing a block only one node has seen.
+    async fn confirm_quorum(
+        &self,
+        served_by: &[usize],
+        block_numbers: &[u64],
+        blocks: Vec<Result<Option<Block>>>,
+    ) -> Vec<Result<Option<Block>>> {
+        if self.quorum <= 1 {
+            return blocks;
+        }
+
+        let mut confirmations = vec![1usize; blocks.len()];
+        let mut pending: Vec<(usize, u64, String)> = blocks
+            .iter()
+            .zip(block_numbers)
+            .enumerate()
+            .filter_map(|(position, (block, &number))| match block {
+                Ok(Some(block)) => Some((position, number, format!("{:#x}", block.header.hash))),
+                _ => None,
+            })
+            .collect();
+
+        for index in self.endpoints.order() {
+            pending.retain(|&(position, _, _)| confirmations[position] < self.quorum);
+            let to_check: Vec<&(usize, u64, String)> = pending
+                .iter()
+                .filter(|(position, _, _)| served_by[*position] != index)
+                .collect();
+            if to_check.is_empty() {
+                continue;
+            }
+
+            let calls: Vec<(&str, Value)> = to_check
+                .iter()
+                .map(|(_, number, _)| ("eth_getBlockByNumber", json!([format!("0x{:x}", number), false])))
+                .collect();
+            match self.call_batch_on(index, "quorum_block_hashes", &calls).await {
+                Ok(responses) => {
+                    self.endpoints.record_success(index);
+                    for ((position, _, hash), response) in to_check.into_iter().zip(responses) {
+                        let agrees = response.is_ok_and(|block| {
+                            block["hash"].as_str().is_some_and(|h| h.eq_ignore_ascii_case(hash))
+                        });
+                        if agrees {
+                            confirmations[*position] += 1;
+                        }
+                    }
+                }
+                Err(e) => {
+                    self.metrics.inc_rpc_errors(e.kind());
+                    if e.is_endpoint_failure() {
+                        self.endpoints.record_failure(index);
+                    }
+                    warn!("Quorum check on {} failed: {}", self.endpoints.label(index), e);
+                }
+            }
+        }
+
+        blocks
+            .into_iter()
+            .zip(block_numbers)
+            .zip(confirmations)
+            .map(|((block, number), confirmed)| match block {
+                Ok(Some(block)) if confirmed < self.quorum => Err(anyhow::anyhow!(
+                    "Block {} hash {:#x} confirmed by {} of {} required endpoints",
+                    number,
+                    block.header.hash,
+                    confirmed,
+                    self.quorum
+                )),
+                other => other,
+            })
+            .collect()
+    }
+
+    /// Decode an `eth_getBlockByNumber` response with full transactions.
+    ///
+    /// Transactions are decoded one by one so a malformed transaction is
+    /// reported by position rather than as a mismatch of the whole block.
+    fn decode_block(&self, block_number: u64, mut value: Value) -> RpcResult<Option<Block>> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let decode_error = |message: String| {
+            self.metrics.inc_rpc_errors("decode");
+            RpcError::Decode(message)
+        };
+
+        let transactions = match value.get_mut("transactions").map(Value::take) {
+            Some(Value::Array(transactions)) => transactions,
+            _ => return Err(decode_error(format!("Block {} missing transactions array", block_number))),
+        };
+        let transactions = transactions
+            .into_iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                serde_json::from_value::<Transaction>(tx).map_err(|e| {
+                    decode_error(format!("Block {} transaction {}: {}", block_number, index, e))
+                })
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        value["transactions"] = json!([]);
+        let block: Block = serde_json::from_value(value)
+            .map_err(|e| decode_error(format!("Block {}: {}", block_number, e)))?;
+        if block.header.number != block_number {
+            return Err(decode_error(format!(
+                "Requested block {} but got block {}",
+                block_number, block.header.number
+            )));
         }
 
-        Ok(result["result"].clone())
+        Ok(Some(block.into_full_block(transactions)))
     }
 
     /// Get the latest block number.
     pub async fn get_latest_block_number(&self) -> Result<u64> {
-        let start = Instant::now();
-        let result = self.call_rpc("eth_blockNumber", json!([])).await?;
-        let duration = start.elapsed().as_secs_f64();
-        self.metrics.observe_rpc_latency("get_block_number", duration);
+        let (_, result) = self.call_rpc("get_block_number", "eth_blockNumber", json!([])).await?;
 
         let hex_str = result.as_str().ok_or_else(|| anyhow::anyhow!("Invalid response"))?;
         let block_num = u64::from_str_radix(hex_str.strip_prefix("0x").unwrap_or(hex_str), 16)?;
@@ -72,20 +554,361 @@ impl RpcClient {
     }
 
     /// Get a block by number with full transaction details.
-    /// Returns the raw JSON block data for flexible parsing.
-    pub async fn get_block(&self, block_number: u64) -> Result<Option<Value>> {
-        let start = Instant::now();
+    ///
+    /// Fails with [`RpcError::Decode`] if the block or any of its
+    /// transactions does not match the expected schema.
+    pub async fn get_block(&self, block_number: u64) -> Result<Option<Block>> {
+        let hex_block = format!("0x{:x}", block_number);
+        let (served_by, result) = self
+            .call_rpc("get_block", "eth_getBlockByNumber", json!([hex_block, true]))
+            .await?;
+        let block = self.decode_block(block_number, result).map_err(anyhow::Error::from);
+
+        let block = self
+            .confirm_quorum(&[served_by], &[block_number], vec![block])
+            .await
+            .pop()
+            .unwrap_or(Ok(None))?;
+
+        if block.is_some() {
+            debug!("Fetched block {}", block_number);
+        }
+        Ok(block)
+    }
+
+    /// Get the hash of a block by number without fetching its transactions.
+    pub async fn get_block_hash(&self, block_number: u64) -> Result<Option<String>> {
         let hex_block = format!("0x{:x}", block_number);
-        let result = self.call_rpc("eth_getBlockByNumber", json!([hex_block, true])).await?;
-        let duration = start.elapsed().as_secs_f64();
-        self.metrics.observe_rpc_latency("get_block", duration);
+        let (_, result) = self
+            .call_rpc("get_block_hash", "eth_getBlockByNumber", json!([hex_block, false]))
+            .await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let hash = result["hash"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Block {} missing hash", block_number))?;
+        Ok(Some(hash.to_string()))
+    }
+
+    /// Get the number and hash of the block currently referenced by a block tag
+    /// (`latest`, `safe` or `finalized`).
+    pub async fn get_tagged_block(&self, tag: &str) -> Result<Option<(u64, String)>> {
+        let (_, result) = self
+            .call_rpc("get_tagged_block", "eth_getBlockByNumber", json!([tag, false]))
+            .await?;
 
         if result.is_null() {
             return Ok(None);
         }
 
-        debug!("Fetched block {}", block_number);
-        Ok(Some(result))
+        let hex_str = result["number"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Block tagged {} missing number", tag))?;
+        let block_num = u64::from_str_radix(hex_str.strip_prefix("0x").unwrap_or(hex_str), 16)?;
+        let hash = result["hash"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Block tagged {} missing hash", tag))?;
+        debug!("Block tagged {}: {}", tag, block_num);
+        Ok(Some((block_num, hash.to_string())))
+    }
+
+    /// Get the timestamp of a block by number without fetching its transactions.
+    pub async fn get_block_timestamp(&self, block_number: u64) -> Result<Option<u64>> {
+        let hex_block = format!("0x{:x}", block_number);
+        let (_, result) = self
+            .call_rpc("get_block_timestamp", "eth_getBlockByNumber", json!([hex_block, false]))
+            .await?;
+
+        if result.is_null() {
+            return Ok(None);
+        }
+
+        let hex_str = result["timestamp"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Block {} missing timestamp", block_number))?;
+        Ok(Some(u64::from_str_radix(hex_str.strip_prefix("0x").unwrap_or(hex_str), 16)?))
+    }
+
+    /// Get several blocks with full transaction details in one batch request.
+    ///
+    /// Results are returned in the same order as `block_numbers`. Blocks
+    /// that cannot be decoded are returned as [`RpcError::Decode`] errors.
+    pub async fn get_blocks(&self, block_numbers: &[u64]) -> Result<Vec<Result<Option<Block>>>> {
+        let calls: Vec<(&str, Value)> = block_numbers
+            .iter()
+            .map(|n| ("eth_getBlockByNumber", json!([format!("0x{:x}", n), true])))
+            .collect();
+        let (served_by, results) = self.call_batch("get_blocks_batch", &calls).await?;
+        let blocks = results
+            .into_iter()
+            .zip(block_numbers)
+            .map(|(result, &number)| {
+                result
+                    .and_then(|value| self.decode_block(number, value))
+                    .map_err(anyhow::Error::from)
+            })
+            .collect();
+
+        debug!("Fetched batch of {} blocks", block_numbers.len());
+        Ok(self.confirm_quorum(&served_by, block_numbers, blocks).await)
+    }
+
+    /// Get the receipts of every transaction in a block with `eth_getBlockReceipts`.
+    ///
+    /// The block is identified by hash, so the receipts cannot come from a
+    /// different block at the same height. This is an enrichment call and
+    /// fails once the daily budget is spent.
+    pub async fn get_block_receipts(&self, block_hash: &str) -> Result<Option<Vec<Value>>> {
+        if self.enrichment_paused() {
+            return Err(RpcError::BudgetExhausted.into());
+        }
+        let (_, result) = self
+            .call_rpc("get_block_receipts", "eth_getBlockReceipts", json!([block_hash]))
+            .await?;
+
+        match result {
+            Value::Null => Ok(None),
+            Value::Array(receipts) => Ok(Some(receipts)),
+            other => Err(anyhow::anyhow!("Invalid eth_getBlockReceipts response: {}", other)),
+        }
+    }
+
+    /// Trace every transaction in a block with the callTracer of
+    /// `debug_traceBlockByNumber`.
+    ///
+    /// This is an enrichment call and fails once the daily budget is spent.
+    pub async fn get_block_call_traces(&self, block_number: u64) -> Result<Option<Vec<Value>>> {
+        if self.enrichment_paused() {
+            return Err(RpcError::BudgetExhausted.into());
+        }
+        let params = json!([format!("0x{:x}", block_number), {"tracer": "callTracer"}]);
+        let (_, result) = self
+            .call_rpc("get_block_traces", "debug_traceBlockByNumber", params)
+            .await?;
+
+        match result {
+            Value::Null => Ok(None),
+            Value::Array(traces) => Ok(Some(traces)),
+            other => Err(anyhow::anyhow!("Invalid debug_traceBlockByNumber response: {}", other)),
+        }
+    }
+
+    /// Get the traces of every transaction in a block with `trace_block`.
+    ///
+    /// This is an enrichment call and fails once the daily budget is spent.
+    pub async fn get_block_parity_traces(&self, block_number: u64) -> Result<Option<Vec<Value>>> {
+        if self.enrichment_paused() {
+            return Err(RpcError::BudgetExhausted.into());
+        }
+        let (_, result) = self
+            .call_rpc("get_block_traces", "trace_block", json!([format!("0x{:x}", block_number)]))
+            .await?;
+
+        match result {
+            Value::Null => Ok(None),
+            Value::Array(traces) => Ok(Some(traces)),
+            other => Err(anyhow::anyhow!("Invalid trace_block response: {}", other)),
+        }
+    }
+
+    /// Get several transaction receipts in one batch request.
+    ///
+    /// Results are returned in the same order as `tx_hashes`. This is an
+    /// enrichment call and fails once the daily budget is spent.
+    pub async fn get_transaction_receipts(&self, tx_hashes: &[String]) -> Result<Vec<Result<Option<Value>>>> {
+        if self.enrichment_paused() {
+            return Err(RpcError::BudgetExhausted.into());
+        }
+        let calls: Vec<(&str, Value)> = tx_hashes
+            .iter()
+            .map(|hash| ("eth_getTransactionReceipt", json!([hash])))
+            .collect();
+        let (_, results) = self.call_batch("get_receipts_batch", &calls).await?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| r.map(non_null).map_err(anyhow::Error::from))
+            .collect())
+    }
+}
+
+fn non_null(value: Value) -> Option<Value> {
+    if value.is_null() {
+        None
+    } else {
+        Some(value)
     }
 }
 
+/// Match batch responses back to their requests by id.
+///
+/// JSON-RPC servers may answer batch entries in any order and may omit
+/// entries entirely, so the output is ordered by `ids` and any missing
+/// response becomes an error for that entry.
+fn match_batch_responses(ids: &[u64], responses: Vec<Value>) -> Vec<RpcResult<Value>> {
+    let mut by_id: HashMap<u64, Value> = responses
+        .into_iter()
+        .filter_map(|response| response["id"].as_u64().map(|id| (id, response)))
+        .collect();
+
+    ids.iter()
+        .map(|id| {
+            let mut response = by_id
+                .remove(id)
+                .ok_or_else(|| RpcError::Decode(format!("No response for batch request id {}", id)))?;
+            if let Some(error) = response.get("error") {
+                return Err(RpcError::from_json_rpc(error));
+            }
+            Ok(response["result"].take())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_metrics;
+    use alloy::primitives::B256;
+    use alloy::rpc::types::BlockTransactions;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    /// Mock JSON-RPC node answering every `eth_getBlockByNumber` call, single
+    /// or batched, with an empty block whose hash is `hash`.
+    async fn mock_node(hash: B256) -> String {
+        let answer = move |request: &Value| {
+            let number = request["params"][0].as_str().unwrap_or("0x0");
+            let mut block: Block = Block::default();
+            block.header.number = u64::from_str_radix(number.trim_start_matches("0x"), 16).unwrap();
+            block.header.hash = hash;
+            block.transactions = BlockTransactions::Full(vec![]);
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": block})
+        };
+        let app = Router::new().route(
+            "/",
+            post(move |Json(payload): Json<Value>| async move {
+                match payload {
+                    Value::Array(requests) => Json(Value::Array(requests.iter().map(answer).collect())),
+                    request => Json(answer(&request)),
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/", addr)
+    }
+
+    /// URL of a port nothing listens on.
+    async fn unreachable_node() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        format!("http://{}/", listener.local_addr().unwrap())
+    }
+
+    fn quorum_client(urls: &[String], quorum: usize) -> RpcClient {
+        let endpoints = urls
+            .iter()
+            .enumerate()
+            .map(|(priority, url)| RpcEndpoint::new(url, priority as u32))
+            .collect();
+        RpcClient::from_endpoints(endpoints, test_metrics())
+            .unwrap()
+            .with_quorum(quorum)
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_retries: 0,
+                ..RetryPolicy::default()
+            })
+    }
+
+    #[tokio::test]
+    async fn test_quorum_confirms_agreeing_block_hashes() {
+        let canonical = B256::repeat_byte(0xaa);
+        let urls = vec![
+            mock_node(canonical).await,
+            mock_node(B256::repeat_byte(0xbb)).await,
+            mock_node(canonical).await,
+        ];
+        let client = quorum_client(&urls, 2);
+
+        // The disagreeing second endpoint is outvoted by the third
+        let block = client.get_block(7).await.unwrap().unwrap();
+        assert_eq!(block.header.hash, canonical);
+
+        let blocks = client.get_blocks(&[7, 8]).await.unwrap();
+        assert!(blocks.iter().all(|block| block.as_ref().is_ok_and(Option::is_some)));
+    }
+
+    #[tokio::test]
+    async fn test_quorum_rejects_disagreeing_block_hashes() {
+        let urls = vec![
+            mock_node(B256::repeat_byte(0xaa)).await,
+            mock_node(B256::repeat_byte(0xbb)).await,
+        ];
+        let client = quorum_client(&urls, 2);
+
+        let error = client.get_block(7).await.unwrap_err();
+        assert!(error.to_string().contains("confirmed by 1 of 2"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_fails_with_too_few_reachable_endpoints() {
+        let hash = B256::repeat_byte(0xaa);
+        let urls = vec![
+            mock_node(hash).await,
+            unreachable_node().await,
+            mock_node(hash).await,
+        ];
+        let client = quorum_client(&urls, 3);
+
+        let blocks = client.get_blocks(&[7]).await.unwrap();
+        let error = blocks[0].as_ref().unwrap_err();
+        assert!(error.to_string().contains("confirmed by 2 of 3"), "{}", error);
+    }
+
+    #[test]
+    fn test_batch_responses_matched_by_id() {
+        let responses = vec![
+            json!({"jsonrpc": "2.0", "id": 12, "result": "0xc"}),
+            json!({"jsonrpc": "2.0", "id": 11, "error": {"code": -32000, "message": "header not found"}}),
+            json!({"jsonrpc": "2.0", "id": 10, "result": "0xa"}),
+        ];
+
+        let results = match_batch_responses(&[10, 11, 12, 13], responses);
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), "0xa");
+        assert!(results[1].as_ref().unwrap_err().to_string().contains("header not found"));
+        assert_eq!(results[2].as_ref().unwrap(), "0xc");
+        assert!(results[3].is_err());
+    }
+
+    #[test]
+    fn test_decode_block_reports_malformed_transactions() {
+        let client = RpcClient::new("http://127.0.0.1:8545", test_metrics()).unwrap();
+        let mut block: Block = Block::default();
+        block.header.number = 7;
+        block.transactions = BlockTransactions::Full(vec![Transaction {
+            hash: B256::repeat_byte(0x01),
+            ..Default::default()
+        }]);
+        let value = serde_json::to_value(&block).unwrap();
+
+        let decoded = client.decode_block(7, value.clone()).unwrap().unwrap();
+        assert_eq!(decoded.transactions.len(), 1);
+        assert!(client.decode_block(7, Value::Null).unwrap().is_none());
+        assert!(matches!(client.decode_block(8, value.clone()), Err(RpcError::Decode(_))));
+
+        let mut malformed = value.clone();
+        malformed["transactions"][0]["maxPriorityFeePerGas"] = json!("0xnothex");
+        let error = client.decode_block(7, malformed).unwrap_err();
+        assert!(matches!(&error, RpcError::Decode(message) if message.contains("transaction 0")));
+
+        let mut hashes_only = value;
+        hashes_only["transactions"] = json!([format!("{:#x}", B256::repeat_byte(0x01))]);
+        assert!(matches!(client.decode_block(7, hashes_only), Err(RpcError::Decode(_))));
+    }
+}
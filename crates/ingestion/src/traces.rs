@@ -0,0 +1,340 @@
+//! Call trace parsing for `debug_traceBlockByNumber` (callTracer) and
+//! `trace_block` responses.
+
+use alloy::primitives::U256;
+use anyhow::Result;
+use mev_africa_db::models::format_wei;
+use serde_json::Value;
+
+/// An internal call made while executing a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallFrame {
+    /// Path of the call in the call tree, e.g. `[0, 2]` is the third subcall
+    /// of the first subcall. Empty for the top-level call.
+    pub trace_address: Vec<usize>,
+    /// CALL, DELEGATECALL, STATICCALL, CALLCODE, CREATE, CREATE2 or SELFDESTRUCT.
+    pub call_type: String,
+    pub from: String,
+    pub to: Option<String>,
+    /// Value transferred in wei, formatted with [`format_wei`]. `None` when zero.
+    pub value: Option<String>,
+    pub gas_used: Option<i64>,
+    /// First four bytes of the call input.
+    pub selector: Option<String>,
+    /// Why this call failed, if it did.
+    pub error: Option<String>,
+    /// Whether this call's effects were undone, because it or one of its
+    /// callers failed.
+    pub reverted: bool,
+}
+
+impl CallFrame {
+    /// Nesting depth; direct subcalls of the transaction have depth 1.
+    pub fn depth(&self) -> usize {
+        self.trace_address.len()
+    }
+
+    /// Trace address as a dot-separated string, e.g. `0.2`.
+    pub fn trace_address_string(&self) -> String {
+        self.trace_address
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Whether this call moved ETH that stayed moved.
+    ///
+    /// DELEGATECALL and CALLCODE frames report the value of the calling
+    /// frame, which was already counted there, so they never count.
+    pub fn is_transfer(&self) -> bool {
+        matches!(self.call_type.as_str(), "CALL" | "CREATE" | "CREATE2" | "SELFDESTRUCT")
+            && self.value.is_some()
+            && !self.reverted
+    }
+}
+
+/// The internal calls of one transaction, excluding the top-level call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionTrace {
+    pub calls: Vec<CallFrame>,
+}
+
+impl TransactionTrace {
+    /// Internal calls that transferred ETH.
+    pub fn transfers(&self) -> impl Iterator<Item = &CallFrame> {
+        self.calls.iter().filter(|call| call.is_transfer())
+    }
+
+    /// Internal calls that failed.
+    pub fn failed_calls(&self) -> impl Iterator<Item = &CallFrame> {
+        self.calls.iter().filter(|call| call.error.is_some())
+    }
+
+    /// Build a trace from every frame of a transaction, including the
+    /// top-level call, marking calls under a failed caller as reverted.
+    fn from_frames(mut frames: Vec<CallFrame>) -> Self {
+        let failed: Vec<Vec<usize>> = frames
+            .iter()
+            .filter(|frame| frame.error.is_some())
+            .map(|frame| frame.trace_address.clone())
+            .collect();
+        for frame in &mut frames {
+            frame.reverted = failed.iter().any(|path| frame.trace_address.starts_with(path));
+        }
+        frames.retain(|frame| !frame.trace_address.is_empty());
+        Self { calls: frames }
+    }
+}
+
+/// Parse a `debug_traceBlockByNumber` response produced by the callTracer.
+///
+/// # Arguments
+/// * `results` - One `{txHash, result}` entry per transaction
+/// * `tx_hashes` - Hashes of the block's transactions, in order
+pub fn parse_call_tracer(results: &[Value], tx_hashes: &[String]) -> Result<Vec<TransactionTrace>> {
+    if results.len() != tx_hashes.len() {
+        return Err(anyhow::anyhow!(
+            "Got {} traces for {} transactions",
+            results.len(),
+            tx_hashes.len()
+        ));
+    }
+
+    results
+        .iter()
+        .zip(tx_hashes)
+        .map(|(entry, tx_hash)| {
+            // Older nodes omit txHash; the position still identifies the transaction
+            if let Some(traced) = entry["txHash"].as_str() {
+                if !traced.eq_ignore_ascii_case(tx_hash) {
+                    return Err(anyhow::anyhow!("Trace for {} does not match transaction {}", traced, tx_hash));
+                }
+            }
+            if let Some(error) = entry["error"].as_str() {
+                return Err(anyhow::anyhow!("Tracing {} failed: {}", tx_hash, error));
+            }
+
+            let mut frames = Vec::new();
+            collect_call_frames(&entry["result"], Vec::new(), &mut frames)?;
+            Ok(TransactionTrace::from_frames(frames))
+        })
+        .collect()
+}
+
+fn collect_call_frames(frame: &Value, trace_address: Vec<usize>, frames: &mut Vec<CallFrame>) -> Result<()> {
+    let call_type = frame["type"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Call frame missing type"))?
+        .to_uppercase();
+    let from = frame["from"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Call frame missing from"))?
+        .to_string();
+
+    frames.push(CallFrame {
+        trace_address: trace_address.clone(),
+        call_type,
+        from,
+        to: frame["to"].as_str().map(str::to_string),
+        value: parse_value(&frame["value"])?,
+        gas_used: frame["gasUsed"].as_str().map(parse_quantity).transpose()?,
+        selector: selector(&frame["input"]),
+        error: frame["error"].as_str().map(str::to_string),
+        reverted: false,
+    });
+
+    if let Some(calls) = frame["calls"].as_array() {
+        for (index, call) in calls.iter().enumerate() {
+            let mut child = trace_address.clone();
+            child.push(index);
+            collect_call_frames(call, child, frames)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse a `trace_block` response (Parity/OpenEthereum trace format).
+///
+/// Block reward traces are skipped.
+///
+/// # Arguments
+/// * `traces` - Flat list of traces for the whole block
+/// * `tx_hashes` - Hashes of the block's transactions, in order
+/// * `block_hash` - Hash of the block the traces must belong to
+pub fn parse_parity_traces(traces: &[Value], tx_hashes: &[String], block_hash: &str) -> Result<Vec<TransactionTrace>> {
+    let mut frames: Vec<Vec<CallFrame>> = vec![Vec::new(); tx_hashes.len()];
+
+    for trace in traces {
+        let Some(position) = trace["transactionPosition"].as_u64() else {
+            continue;
+        };
+        let position = position as usize;
+        let tx_hash = tx_hashes
+            .get(position)
+            .ok_or_else(|| anyhow::anyhow!("Trace for transaction position {} outside the block", position))?;
+        let matches = |field: &str, expected: &str| {
+            trace[field].as_str().is_some_and(|v| v.eq_ignore_ascii_case(expected))
+        };
+        if !matches("transactionHash", tx_hash) || !matches("blockHash", block_hash) {
+            return Err(anyhow::anyhow!("Trace for {} does not belong to block {}", tx_hash, block_hash));
+        }
+
+        frames[position].push(parse_parity_trace(trace)?);
+    }
+
+    Ok(frames.into_iter().map(TransactionTrace::from_frames).collect())
+}
+
+fn parse_parity_trace(trace: &Value) -> Result<CallFrame> {
+    let action = &trace["action"];
+    let trace_address = trace["traceAddress"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Trace missing traceAddress"))?
+        .iter()
+        .map(|index| {
+            index
+                .as_u64()
+                .map(|i| i as usize)
+                .ok_or_else(|| anyhow::anyhow!("Invalid traceAddress"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (call_type, from, to, input) = match trace["type"].as_str() {
+        Some("call") => (
+            action["callType"].as_str().unwrap_or("call").to_uppercase(),
+            &action["from"],
+            action["to"].as_str().map(str::to_string),
+            &action["input"],
+        ),
+        Some("create") => (
+            action["creationMethod"].as_str().unwrap_or("create").to_uppercase(),
+            &action["from"],
+            trace["result"]["address"].as_str().map(str::to_string),
+            &action["init"],
+        ),
+        Some("suicide") => (
+            "SELFDESTRUCT".to_string(),
+            &action["address"],
+            action["refundAddress"].as_str().map(str::to_string),
+            &Value::Null,
+        ),
+        other => return Err(anyhow::anyhow!("Unsupported trace type {:?}", other)),
+    };
+    let from = from
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Trace missing sender"))?
+        .to_string();
+    let value = if trace["type"] == "suicide" {
+        parse_value(&action["balance"])?
+    } else {
+        parse_value(&action["value"])?
+    };
+
+    Ok(CallFrame {
+        trace_address,
+        call_type,
+        from,
+        to,
+        value,
+        gas_used: trace["result"]["gasUsed"].as_str().map(parse_quantity).transpose()?,
+        selector: selector(input),
+        error: trace["error"].as_str().map(str::to_string),
+        reverted: false,
+    })
+}
+
+/// Parse a hex wei amount, treating zero as absent.
+fn parse_value(value: &Value) -> Result<Option<String>> {
+    let Some(hex) = value.as_str() else {
+        return Ok(None);
+    };
+    let wei: U256 = hex.parse()?;
+    Ok((!wei.is_zero()).then(|| format_wei(wei)))
+}
+
+fn parse_quantity(hex: &str) -> Result<i64> {
+    Ok(i64::from_str_radix(hex.strip_prefix("0x").unwrap_or(hex), 16)?)
+}
+
+fn selector(input: &Value) -> Option<String> {
+    let input = input.as_str()?;
+    let hex = input.strip_prefix("0x").unwrap_or(input);
+    hex.get(..8).map(|selector| format!("0x{}", selector))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_call_tracer_marks_reverted_transfers() {
+        let results = vec![json!({
+            "txHash": "0xtx1",
+            "result": {
+                "type": "CALL", "from": "0xeoa", "to": "0xbot", "value": "0x0",
+                "gasUsed": "0x1000", "input": "0xdeadbeef00",
+                "calls": [
+                    {"type": "CALL", "from": "0xbot", "to": "0xbuilder", "value": "0xde0b6b3a7640000", "input": "0x"},
+                    {
+                        "type": "DELEGATECALL", "from": "0xbot", "to": "0xlogic", "input": "0x022c0d9f0000",
+                        "error": "execution reverted",
+                        "calls": [
+                            {"type": "CALL", "from": "0xbot", "to": "0xpair", "value": "0x1", "input": "0x"}
+                        ]
+                    },
+                    {"type": "DELEGATECALL", "from": "0xbot", "to": "0xlogic", "value": "0xde0b6b3a7640000", "input": "0x"}
+                ]
+            }
+        })];
+
+        let traces = parse_call_tracer(&results, &["0xtx1".to_string()]).unwrap();
+        let trace = &traces[0];
+        assert_eq!(trace.calls.len(), 4);
+        assert_eq!(trace.calls[2].trace_address_string(), "1.0");
+        assert_eq!(trace.calls[1].selector.as_deref(), Some("0x022c0d9f"));
+
+        let transfers: Vec<_> = trace.transfers().collect();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].value.as_deref(), Some("1000000000000000000"));
+        assert!(trace.calls[2].reverted);
+        // The DELEGATECALL carries the caller's value but moves no ETH
+        assert_eq!(trace.calls[3].value.as_deref(), Some("1000000000000000000"));
+        assert!(!trace.calls[3].is_transfer());
+
+        let failed: Vec<_> = trace.failed_calls().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].call_type, "DELEGATECALL");
+
+        assert!(parse_call_tracer(&results, &["0xother".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parity_traces_are_grouped_by_transaction() {
+        let traces = vec![
+            json!({
+                "type": "call", "transactionPosition": 1, "transactionHash": "0xtx2", "blockHash": "0xblock",
+                "traceAddress": [], "action": {"callType": "call", "from": "0xeoa", "to": "0xbot", "value": "0x0", "input": "0x"},
+                "result": {"gasUsed": "0x10", "output": "0x"}
+            }),
+            json!({
+                "type": "call", "transactionPosition": 1, "transactionHash": "0xtx2", "blockHash": "0xblock",
+                "traceAddress": [0], "action": {"callType": "staticcall", "from": "0xbot", "to": "0xpair", "value": "0x0", "input": "0x0902f1ac"},
+                "result": {"gasUsed": "0x5", "output": "0x"}
+            }),
+            json!({"type": "reward", "blockHash": "0xblock", "traceAddress": [], "action": {"author": "0xminer"}}),
+        ];
+        let tx_hashes = vec!["0xtx1".to_string(), "0xtx2".to_string()];
+
+        let parsed = parse_parity_traces(&traces, &tx_hashes, "0xblock").unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[0].calls.is_empty());
+        assert_eq!(parsed[1].calls.len(), 1);
+        assert_eq!(parsed[1].calls[0].call_type, "STATICCALL");
+        assert_eq!(parsed[1].calls[0].selector.as_deref(), Some("0x0902f1ac"));
+        assert_eq!(parsed[1].calls[0].gas_used, Some(5));
+
+        assert!(parse_parity_traces(&traces, &tx_hashes, "0xother").is_err());
+    }
+}
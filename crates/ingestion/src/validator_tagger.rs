@@ -8,6 +8,7 @@ use tracing::{debug, info};
 /// Validator tagger for identifying Africa-related blocks.
 pub struct ValidatorTagger {
     africa_fee_recipients: HashSet<String>,
+    africa_pubkeys: HashSet<String>,
 }
 
 impl ValidatorTagger {
@@ -16,20 +17,18 @@ impl ValidatorTagger {
     /// # Arguments
     /// * `db` - Database pool
     pub async fn new(db: &DbPool) -> anyhow::Result<Self> {
-        let rows = sqlx::query("SELECT DISTINCT fee_recipient FROM validators")
-            .fetch_all(db.pool())
-            .await?;
-
-        let mut fee_recipients = HashSet::new();
-        for row in rows {
-            let fee_recipient: String = row.get(0);
-            fee_recipients.insert(fee_recipient.to_lowercase());
-        }
+        let mut tagger = Self {
+            africa_fee_recipients: HashSet::new(),
+            africa_pubkeys: HashSet::new(),
+        };
+        tagger.load(db).await?;
 
-        info!("Loaded {} Africa validator fee recipients", fee_recipients.len());
-        Ok(Self {
-            africa_fee_recipients: fee_recipients,
-        })
+        info!(
+            "Loaded {} Africa validator fee recipients and {} pubkeys",
+            tagger.africa_fee_recipients.len(),
+            tagger.africa_pubkeys.len()
+        );
+        Ok(tagger)
     }
 
     /// Check if a fee recipient is associated with Africa validators.
@@ -48,20 +47,51 @@ impl ValidatorTagger {
         is_tagged
     }
 
+    /// Check if a validator pubkey belongs to an Africa validator.
+    ///
+    /// Operators often share fee recipients with pools, so matching the
+    /// proposer's pubkey is more reliable than matching the fee recipient.
+    ///
+    /// # Arguments
+    /// * `pubkey` - The proposer's BLS public key as hex
+    ///
+    /// # Returns
+    /// True if the pubkey matches an Africa validator
+    pub fn is_africa_pubkey(&self, pubkey: &str) -> bool {
+        let normalized = pubkey.to_lowercase();
+        let is_tagged = self.africa_pubkeys.contains(&normalized);
+        if is_tagged {
+            debug!("Proposer {} tagged as Africa validator", pubkey);
+        }
+        is_tagged
+    }
+
     /// Refresh the validator list from the database.
     pub async fn refresh(&mut self, db: &DbPool) -> anyhow::Result<()> {
-        let rows = sqlx::query("SELECT DISTINCT fee_recipient FROM validators")
+        self.load(db).await?;
+
+        info!(
+            "Refreshed {} Africa validator fee recipients and {} pubkeys",
+            self.africa_fee_recipients.len(),
+            self.africa_pubkeys.len()
+        );
+        Ok(())
+    }
+
+    async fn load(&mut self, db: &DbPool) -> anyhow::Result<()> {
+        let rows = sqlx::query("SELECT fee_recipient, validator_pubkey FROM validators")
             .fetch_all(db.pool())
             .await?;
 
         self.africa_fee_recipients.clear();
+        self.africa_pubkeys.clear();
         for row in rows {
             let fee_recipient: String = row.get(0);
+            let pubkey: String = row.get(1);
             self.africa_fee_recipients.insert(fee_recipient.to_lowercase());
+            self.africa_pubkeys.insert(pubkey.to_lowercase());
         }
 
-        info!("Refreshed {} Africa validator fee recipients", self.africa_fee_recipients.len());
         Ok(())
     }
 }
-
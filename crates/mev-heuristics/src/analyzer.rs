@@ -11,7 +11,7 @@ pub struct TransactionAnalysis {
     pub is_mev_candidate: bool,
     /// Reason codes for MEV detection.
     pub reason_codes: Vec<MevReasonCode>,
-    /// Summary of calldata (first 100 bytes as hex).
+    /// Summary of calldata (`0x` and the first 100 bytes as hex).
     pub calldata_summary: Option<String>,
     /// Summary of logs (count and topics).
     pub log_summary: Option<String>,
@@ -41,9 +41,9 @@ impl TransactionAnalyzer {
         let calldata_summary = if !tx.input.is_empty() {
             let hex_str = hex::encode(tx.input.as_ref());
             Some(if hex_str.len() > 200 {
-                format!("{}...", &hex_str[..200])
+                format!("0x{}...", &hex_str[..200])
             } else {
-                hex_str
+                format!("0x{}", hex_str)
             })
         } else {
             None
@@ -1,8 +1,8 @@
 //! MEV detection heuristics.
 
+use alloy::primitives::U256;
 use alloy::rpc::types::Transaction;
 use mev_africa_db::models::MevReasonCode as DbMevReasonCode;
-use rust_decimal::Decimal;
 
 /// Analyze a transaction for MEV patterns.
 ///
@@ -46,14 +46,14 @@ pub fn detect_mev_patterns(
 /// Check if transaction has unusually high priority fee relative to block median.
 fn is_high_priority_fee_outlier(tx: &Transaction, block_txs: &[&Transaction]) -> bool {
     let tx_priority_fee = match tx.max_priority_fee_per_gas {
-        Some(fee) => Decimal::from(fee as u64),
+        Some(fee) => U256::from(fee),
         None => return false,
     };
 
     // Calculate median priority fee for the block
-    let mut fees: Vec<Decimal> = block_txs
+    let mut fees: Vec<U256> = block_txs
         .iter()
-        .filter_map(|t| t.max_priority_fee_per_gas.map(|f| Decimal::from(f as u64)))
+        .filter_map(|t| t.max_priority_fee_per_gas.map(U256::from))
         .collect();
 
     if fees.is_empty() {
@@ -61,14 +61,15 @@ fn is_high_priority_fee_outlier(tx: &Transaction, block_txs: &[&Transaction]) ->
     }
 
     fees.sort();
-    let median = if fees.len() % 2 == 0 {
-        (fees[fees.len() / 2 - 1] + fees[fees.len() / 2]) / Decimal::from(2)
+    let median = if fees.len().is_multiple_of(2) {
+        (fees[fees.len() / 2 - 1] + fees[fees.len() / 2]) / U256::from(2)
     } else {
         fees[fees.len() / 2]
     };
 
-    // Flag if priority fee is more than 3x the median
-    tx_priority_fee > median * Decimal::from(3)
+    // Flag if priority fee is more than 3x the median. A zero median would
+    // flag every transaction that tips at all.
+    !median.is_zero() && tx_priority_fee > median.saturating_mul(U256::from(3))
 }
 
 /// Check if sender appears multiple times in the block (potential bot activity).
@@ -95,10 +96,14 @@ fn is_atomic_multiswap(tx: &Transaction) -> bool {
     if !tx.input.is_empty() {
         let input_str = hex::encode(tx.input.as_ref());
         // Look for common swap function selectors
-        // Uniswap V2: 0x7ff36ab5 (swapExactETHForTokens)
+        // Uniswap V2 pair: 0x022c0d9f (swap)
+        // Uniswap V2 router: 0x7ff36ab5 (swapExactETHForTokens)
         // Uniswap V3: 0x414bf389 (exactInputSingle)
+        // Uniswap router 02: 0x472b43f3 (swapExactTokensForTokens)
         // 0x5c11d795 (multicall)
         let swap_patterns = [
+            "022c0d9f", // swap
+            "472b43f3", // swapExactTokensForTokens
             "7ff36ab5", // swapExactETHForTokens
             "414bf389", // exactInputSingle
             "5c11d795", // multicall
@@ -128,11 +133,13 @@ fn is_sandwich_pattern(tx: &Transaction, block_txs: &[&Transaction], tx_index: u
     let sender = tx.from.to_string();
 
     // Check if same sender has transactions before and after this one
-    let has_before = block_txs[..tx_index]
+    let has_before = block_txs[..tx_index.min(block_txs.len())]
         .iter()
         .any(|t| t.from.to_string() == sender);
 
-    let has_after = block_txs[tx_index + 1..]
+    let has_after = block_txs
+        .get(tx_index + 1..)
+        .unwrap_or_default()
         .iter()
         .any(|t| t.from.to_string() == sender);
 
@@ -145,27 +152,11 @@ mod tests {
     use alloy::primitives::{Address, U256};
 
     fn create_test_tx(sender: Address, priority_fee: Option<u64>) -> Transaction {
-        use alloy::primitives::Bytes;
         Transaction {
-            hash: Default::default(),
-            nonce: 0,
-            block_hash: None,
-            block_number: None,
-            transaction_index: None,
             from: sender,
-            to: None,
             value: U256::ZERO,
-            gas_price: None,
-            max_fee_per_gas: None,
-            max_priority_fee_per_gas: priority_fee.map(U256::from),
-            gas: U256::ZERO,
-            input: Bytes::new(),
-            chain_id: None,
-            v: 0,
-            r: Default::default(),
-            s: Default::default(),
-            access_list: None,
-            transaction_type: None,
+            max_priority_fee_per_gas: priority_fee.map(u128::from),
+            ..Default::default()
         }
     }
 
@@ -181,7 +172,8 @@ mod tests {
 
         // Add outlier with 10 gwei
         let outlier = create_test_tx(Address::ZERO, Some(10_000_000_000));
-        let reasons = detect_mev_patterns(&outlier, &block_txs, 10);
+        let block_refs: Vec<&Transaction> = block_txs.iter().collect();
+        let reasons = detect_mev_patterns(&outlier, &block_refs, 10);
         assert!(reasons.contains(&DbMevReasonCode::HighPriorityFee));
     }
 
@@ -193,8 +185,37 @@ mod tests {
             block_txs.push(create_test_tx(sender, Some(1_000_000_000)));
         }
 
-        let reasons = detect_mev_patterns(&block_txs[0], &block_txs, 0);
+        let block_refs: Vec<&Transaction> = block_txs.iter().collect();
+        let reasons = detect_mev_patterns(&block_txs[0], &block_refs, 0);
         assert!(reasons.contains(&DbMevReasonCode::RepeatedSender));
     }
-}
 
+    #[test]
+    fn test_zero_median_priority_fee_flags_nothing() {
+        let mut block_txs: Vec<Transaction> = (1..=4u8)
+            .map(|i| create_test_tx(Address::from([i; 20]), Some(0)))
+            .collect();
+        let tipper = create_test_tx(Address::from([5; 20]), Some(1_000_000_000));
+        block_txs.push(tipper.clone());
+
+        // The median is 0, so any tip would be more than 3x the median
+        let block_refs: Vec<&Transaction> = block_txs.iter().collect();
+        let reasons = detect_mev_patterns(&tipper, &block_refs, 4);
+        assert!(!reasons.contains(&DbMevReasonCode::HighPriorityFee));
+    }
+
+    #[test]
+    fn test_priority_fees_above_u64_are_not_truncated() {
+        let mut block_txs: Vec<Transaction> = (0..5)
+            .map(|_| create_test_tx(Address::ZERO, Some(2_000_000_000)))
+            .collect();
+        // 2^64 + 1 wei would wrap to 1 wei if cast to u64
+        let mut outlier = create_test_tx(Address::from([2; 20]), None);
+        outlier.max_priority_fee_per_gas = Some(u128::from(u64::MAX) + 2);
+        block_txs.push(outlier.clone());
+
+        let block_refs: Vec<&Transaction> = block_txs.iter().collect();
+        let reasons = detect_mev_patterns(&outlier, &block_refs, 5);
+        assert!(reasons.contains(&DbMevReasonCode::HighPriorityFee));
+    }
+}
@@ -1,29 +1,84 @@
 //! Audit logging for sample payloads.
 
 use serde::Serialize;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::Path;
-use tracing::info;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
 
-/// Write a sample JSON payload to the audit file.
+/// How often buffered audit samples are written to disk.
+pub const AUDIT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Buffered audit sample writer that keeps the audit file open.
 ///
-/// # Arguments
-/// * `path` - Path to the audit file
-/// * `payload` - Serializable payload to write
-pub fn write_audit_sample<P: AsRef<Path>, T: Serialize>(
-    path: Option<P>,
-    payload: &T,
-) -> anyhow::Result<()> {
-    if let Some(audit_path) = path {
+/// The file is opened on the first write. Buffered samples are written out
+/// when the buffer fills, by the first write after [`AUDIT_FLUSH_INTERVAL`]
+/// has passed since the last flush, on [`AuditWriter::flush`] and on drop.
+pub struct AuditWriter {
+    path: PathBuf,
+    file: Mutex<AuditFile>,
+}
+
+struct AuditFile {
+    writer: Option<BufWriter<File>>,
+    last_flush: Instant,
+}
+
+impl AuditFile {
+    fn flush(&mut self) -> anyhow::Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush()?;
+            writer.get_ref().sync_data()?;
+        }
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+}
+
+impl AuditWriter {
+    /// Create a writer appending to `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            file: Mutex::new(AuditFile {
+                writer: None,
+                last_flush: Instant::now(),
+            }),
+        }
+    }
+
+    /// Append a sample JSON payload to the audit file.
+    pub fn write<T: Serialize>(&self, payload: &T) -> anyhow::Result<()> {
         let json = serde_json::to_string_pretty(payload)?;
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&audit_path)?;
-        writeln!(file, "{}", json)?;
-        info!("Wrote audit sample to {:?}", audit_path.as_ref());
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if file.writer.is_none() {
+            let opened = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            file.writer = Some(BufWriter::new(opened));
+        }
+        if let Some(writer) = file.writer.as_mut() {
+            writeln!(writer, "{}", json)?;
+        }
+        debug!("Buffered audit sample for {:?}", self.path);
+
+        if file.last_flush.elapsed() >= AUDIT_FLUSH_INTERVAL {
+            file.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write any buffered samples to disk.
+    pub fn flush(&self) -> anyhow::Result<()> {
+        self.file.lock().unwrap_or_else(|e| e.into_inner()).flush()
     }
-    Ok(())
 }
 
+impl Drop for AuditWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
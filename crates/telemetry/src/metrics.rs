@@ -1,8 +1,9 @@
 //! Prometheus metrics for MEV Africa data collection.
 
 use prometheus::{
-    register_histogram_vec, register_int_counter, HistogramVec, IntCounter,
-    Encoder, TextEncoder,
+    register_gauge, register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Encoder, TextEncoder,
 };
 
 /// Metrics collector for the MEV Africa service.
@@ -12,8 +13,18 @@ pub struct Metrics {
     transactions_processed: IntCounter,
     mev_candidate_count: IntCounter,
     africa_tagged_blocks: IntCounter,
-    rpc_errors: IntCounter,
+    rpc_errors: IntCounterVec,
     rpc_latency: HistogramVec,
+    rpc_endpoint_healthy: IntGaugeVec,
+    rpc_budget_remaining: IntGauge,
+    rpc_throttle_wait: Histogram,
+    reorgs: IntCounter,
+    finalized_block: IntGauge,
+    backfill_remaining_blocks: IntGauge,
+    backfill_blocks_per_second: Gauge,
+    backfill_eta_seconds: Gauge,
+    missing_blocks: IntGauge,
+    block_write_failures: IntCounterVec,
 }
 
 impl Metrics {
@@ -39,15 +50,68 @@ impl Metrics {
             "Total number of blocks tagged as Africa-related"
         )?;
 
-        let rpc_errors = register_int_counter!(
+        let rpc_errors = register_int_counter_vec!(
             "mev_africa_rpc_errors_total",
-            "Total number of RPC errors"
+            "Total number of RPC errors, by error kind",
+            &["kind"]
         )?;
 
         let rpc_latency = register_histogram_vec!(
             "mev_africa_rpc_latency_seconds",
             "RPC call latency in seconds",
-            &["operation"]
+            &["operation", "endpoint"]
+        )?;
+
+        let rpc_endpoint_healthy = register_int_gauge_vec!(
+            "mev_africa_rpc_endpoint_healthy",
+            "Whether an RPC endpoint is currently considered healthy (1) or cooling down (0)",
+            &["endpoint"]
+        )?;
+
+        let rpc_budget_remaining = register_int_gauge!(
+            "mev_africa_rpc_budget_remaining",
+            "Compute units left in today's RPC budget"
+        )?;
+
+        let rpc_throttle_wait = register_histogram!(
+            "mev_africa_rpc_throttle_wait_seconds",
+            "Time RPC requests waited for the client-side rate limiter in seconds"
+        )?;
+
+        let reorgs = register_int_counter!(
+            "mev_africa_reorgs_total",
+            "Total number of chain reorganizations detected"
+        )?;
+
+        let finalized_block = register_int_gauge!(
+            "mev_africa_finalized_block_number",
+            "Latest finalized block number reported by the execution node"
+        )?;
+
+        let backfill_remaining_blocks = register_int_gauge!(
+            "mev_africa_backfill_remaining_blocks",
+            "Number of blocks remaining in the current backfill"
+        )?;
+
+        let backfill_blocks_per_second = register_gauge!(
+            "mev_africa_backfill_blocks_per_second",
+            "Backfill throughput in blocks per second"
+        )?;
+
+        let backfill_eta_seconds = register_gauge!(
+            "mev_africa_backfill_eta_seconds",
+            "Estimated seconds until the current backfill completes"
+        )?;
+
+        let missing_blocks = register_int_gauge!(
+            "mev_africa_missing_blocks",
+            "Number of blocks missing between the lowest and highest stored block"
+        )?;
+
+        let block_write_failures = register_int_counter_vec!(
+            "mev_africa_block_write_failures_total",
+            "Total number of blocks that failed to be stored, by failure kind",
+            &["kind"]
         )?;
 
         Ok(Self {
@@ -57,6 +121,16 @@ impl Metrics {
             africa_tagged_blocks,
             rpc_errors,
             rpc_latency,
+            rpc_endpoint_healthy,
+            rpc_budget_remaining,
+            rpc_throttle_wait,
+            reorgs,
+            finalized_block,
+            backfill_remaining_blocks,
+            backfill_blocks_per_second,
+            backfill_eta_seconds,
+            missing_blocks,
+            block_write_failures,
         })
     }
 
@@ -80,14 +154,60 @@ impl Metrics {
         self.africa_tagged_blocks.inc();
     }
 
-    /// Increment the RPC errors counter.
-    pub fn inc_rpc_errors(&self) {
-        self.rpc_errors.inc();
+    /// Increment the RPC errors counter for an error kind.
+    pub fn inc_rpc_errors(&self, kind: &str) {
+        self.rpc_errors.with_label_values(&[kind]).inc();
+    }
+
+    /// Record RPC latency for a call served by an endpoint.
+    pub fn observe_rpc_latency(&self, operation: &str, endpoint: &str, duration_secs: f64) {
+        self.rpc_latency
+            .with_label_values(&[operation, endpoint])
+            .observe(duration_secs);
+    }
+
+    /// Set whether an RPC endpoint is healthy.
+    pub fn set_rpc_endpoint_healthy(&self, endpoint: &str, healthy: bool) {
+        self.rpc_endpoint_healthy
+            .with_label_values(&[endpoint])
+            .set(healthy as i64);
+    }
+
+    /// Set the compute units left in today's RPC budget.
+    pub fn set_rpc_budget_remaining(&self, compute_units: u64) {
+        self.rpc_budget_remaining.set(compute_units as i64);
+    }
+
+    /// Record how long a request waited for the rate limiter.
+    pub fn observe_rpc_throttle_wait(&self, wait_secs: f64) {
+        self.rpc_throttle_wait.observe(wait_secs);
+    }
+
+    /// Increment the chain reorganizations counter.
+    pub fn inc_reorgs(&self) {
+        self.reorgs.inc();
+    }
+
+    /// Set the latest finalized block number.
+    pub fn set_finalized_block(&self, block_number: u64) {
+        self.finalized_block.set(block_number as i64);
+    }
+
+    /// Record backfill progress.
+    pub fn set_backfill_progress(&self, remaining_blocks: u64, blocks_per_second: f64, eta_seconds: f64) {
+        self.backfill_remaining_blocks.set(remaining_blocks as i64);
+        self.backfill_blocks_per_second.set(blocks_per_second);
+        self.backfill_eta_seconds.set(eta_seconds);
+    }
+
+    /// Set the number of missing blocks found by the last gap scan.
+    pub fn set_missing_blocks(&self, count: u64) {
+        self.missing_blocks.set(count as i64);
     }
 
-    /// Record RPC latency.
-    pub fn observe_rpc_latency(&self, operation: &str, duration_secs: f64) {
-        self.rpc_latency.with_label_values(&[operation]).observe(duration_secs);
+    /// Increment the block write failures counter for a failure kind.
+    pub fn inc_block_write_failures(&self, kind: &str) {
+        self.block_write_failures.with_label_values(&[kind]).inc();
     }
 
     /// Get Prometheus metrics as a string.